@@ -0,0 +1,1187 @@
+//! Checks the types of an AST before it is interpreted, using Hindley–Milner
+//! style inference. Every expression is given a type, with type variables
+//! solved by unification, so that un-annotated functions get their most
+//! general type: `func id(x) { return x; }` is `func(a) -> a`, and can be
+//! used with any type of argument.
+//!
+//! Types come from annotations (`w: int`, `-> float`, `let name: string`),
+//! literals, operations and calls. Anything which cannot be known before
+//! running, such as module members, is given the `any` type which never
+//! conflicts. Integers are accepted where floats are annotated, and are
+//! converted to floats at runtime, but a float is never accepted where an
+//! integer is expected. Branches and list elements must have the same type.
+
+pub mod types;
+
+use std::collections::{HashMap, HashSet};
+
+use types::{describe, FunctionType, Scheme, Type};
+
+use crate::{
+    errors::{self, Error, ErrorType},
+    interpreter::{iterators::contains_yield, methods::result::RESULT_METHODS, modules::module_name, symbol_table::SymbolTable, symbols::{FunctionSymbol, ParameterType, SymbolType}},
+    lexer::tokens::{Keyword, Token, TokenPosition, TokenType},
+    parser::nodes::{FuncDefNode, MethodCallNode, Node, NodeVisit, Pattern},
+};
+
+/// Names of top-level functions and their inferred types
+pub type Signatures = Vec<(String, String)>;
+
+/// A name, its type and where it was declared
+#[derive(Debug, Clone)]
+struct Binding {
+    scheme: Scheme,
+    position: TokenPosition,
+    /// Type the name was annotated with, which later assignments must keep
+    declared: Option<Type>,
+    /// Type variables the scheme is not generic over, which other
+    /// types cannot be generalized over while the name is in scope
+    free: Vec<u32>,
+}
+
+/// Function whose body is being checked
+struct FunctionContext {
+    name: String,
+    returns: Type,
+    /// Where the return type was first decided, by an annotation or a return
+    origin: Option<TokenPosition>,
+    /// Type of the values yielded, if the function is a generator
+    yields: Option<Type>,
+}
+
+/// Walks an AST inferring the type of every expression, collecting errors
+pub struct Checker {
+    scopes: Vec<HashMap<String, Binding>>,
+    functions: Vec<FunctionContext>,
+    /// Solved type variables
+    substitution: HashMap<u32, Type>,
+    /// Type variables which can only be numbers, as they were used in arithmetic
+    numeric: HashSet<u32>,
+    next_var: u32,
+    errors: Vec<Error>,
+}
+
+/// Check a program, returning the types of its top-level functions,
+/// or every error found in source order
+pub fn check(ast: &Node) -> Result<Signatures, Vec<Error>> {
+    let mut checker = Checker::new();
+    checker.scopes.push(HashMap::new());
+    checker.check_statements(ast);
+
+    let mut errors = std::mem::take(&mut checker.errors);
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| (e.position.line, e.position.column));
+        return Err(errors);
+    }
+
+    let mut functions: Vec<(&String, &Binding)> = checker.scopes[1].iter()
+        .filter(|(_, binding)| matches!(binding.scheme.ty, Type::Function(_)))
+        .collect();
+    functions.sort_by_key(|(_, binding)| (binding.position.line, binding.position.column));
+
+    Ok(functions.into_iter().map(|(name, binding)| (name.clone(), checker.resolve_deep(&binding.scheme.ty).to_string())).collect())
+}
+
+impl Checker {
+    /// Create a checker whose outermost scope contains the built-ins
+    pub fn new() -> Checker {
+        let mut checker = Checker {
+            scopes: vec![HashMap::new()],
+            functions: Vec::new(),
+            substitution: HashMap::new(),
+            numeric: HashSet::new(),
+            next_var: 0,
+            errors: Vec::new(),
+        };
+
+        for (name, symbol) in SymbolTable::new_global().symbols {
+            let ty = match symbol.value {
+                SymbolType::Function(ref function) => checker.builtin_type(&name, function),
+                SymbolType::None => Type::Option(Box::new(checker.fresh())),
+                SymbolType::List(_) if name == "args" => Type::List(Box::new(Type::String)),
+                _ => Type::Dynamic,
+            };
+
+            let scheme = checker.generalize(&ty);
+            checker.bind(name, scheme, TokenPosition::internal(), None);
+        }
+
+        checker
+    }
+
+    fn check_statements(&mut self, node: &Node) {
+        if let Node::StatementsNode(ref statements) = node {
+            for statement in &statements.statements {
+                self.infer(statement);
+            }
+        }
+    }
+
+    /// Infer the type of the value of a node
+    fn infer(&mut self, node: &Node) -> Type {
+        match node {
+            Node::NumberNode(node) => match node.token.value {
+                TokenType::Float(_) => Type::Float,
+                _ => Type::Integer,
+            },
+            Node::StringNode(_) => Type::String,
+            Node::BinOpNode(node) => {
+                let left = self.infer(&node.left_node);
+                let right = self.infer(&node.right_node);
+                self.infer_binary_operation(&node.op_token, (&left, &node.left_node), (&right, &node.right_node))
+            },
+            Node::CompareNode(node) => {
+                let mut left = (self.infer(&node.first), &node.first);
+
+                for comparison in &node.comparisons {
+                    let right = (self.infer(&comparison.operand), &comparison.operand);
+
+                    match comparison.is_membership() {
+                        true => self.check_membership(&left.0, &right.0, &comparison.operator.position),
+                        false => { self.infer_binary_operation(&comparison.operator, (&left.0, left.1), (&right.0, right.1)); },
+                    }
+
+                    left = right;
+                }
+
+                Type::Integer
+            },
+            Node::UnaryOpNode(node) => {
+                let right = self.infer(&node.right);
+
+                match node.token.value {
+                    TokenType::Keyword(Keyword::Not) => {
+                        self.expect(&Type::Integer, &right, &node.right.span(), "Cannot apply not to");
+                        Type::Integer
+                    },
+                    _ => {
+                        if !self.constrain_numeric(&right) {
+                            let right = self.describe(&[&right])[0].clone();
+                            self.error(format!("Cannot negate {}", right), &node.right.span());
+                        }
+                        right
+                    },
+                }
+            },
+            Node::VarAssignmentNode(node) => {
+                let value = self.infer(&node.value);
+                let annotation = node.annotation.as_ref().map(|x| self.annotation(x));
+                self.assign(&node.identifier, value, annotation, &node.value.span());
+                Type::None
+            },
+            Node::ConstAssignmentNode(node) => {
+                let value = self.infer(&node.value);
+                self.assign(&node.identifier, value, None, &node.value.span());
+                Type::None
+            },
+            Node::VarArithmeticAssignmentNode(node) => {
+                let value = self.infer(&node.value);
+                let current = self.lookup(&node.identifier);
+                let access = Node::VarAccessNode(Box::new(crate::parser::nodes::VarAccessNode::new(node.identifier.clone())));
+                let result = self.infer_binary_operation(&node.op_token, (&current, &access), (&value, &node.value));
+                self.assign(&node.identifier, result, None, &node.value.span());
+                Type::None
+            },
+            Node::DestructuringAssignmentNode(node) => {
+                let value = self.infer(&node.value);
+                self.bind_pattern(&node.pattern, &value);
+                Type::None
+            },
+            Node::VarAccessNode(node) => self.lookup(&node.identifier),
+            Node::IfExprNode(node) => {
+                let condition = self.infer(&node.condition);
+                self.expect(&Type::Integer, &condition, &node.condition.span(), "If condition must be an integer, found");
+
+                let if_true = self.infer(&node.if_true);
+
+                // a branch which yields makes the if a statement, so its value is not used
+                if contains_yield(&node.if_true) || node.if_false.as_ref().is_some_and(contains_yield) {
+                    if let Some(ref if_false) = node.if_false {
+                        self.infer(if_false);
+                    }
+                    return Type::None;
+                }
+
+                match node.if_false {
+                    Some(ref if_false) => {
+                        let if_false_type = self.infer(if_false);
+
+                        match self.unify(&if_true, &if_false_type) {
+                            true => if_true,
+                            false => {
+                                let types = self.describe(&[&if_true, &if_false_type]);
+                                self.errors.push(
+                                    Error::new_runtime(ErrorType::TypeError, format!("If branches have different types: {} and {}", types[0], types[1]), &if_false.span())
+                                        .labelled(types[1].clone())
+                                        .with_label(&node.if_true.span(), types[0].clone())
+                                );
+                                Type::Dynamic
+                            },
+                        }
+                    },
+                    None => Type::Dynamic,
+                }
+            },
+            Node::ForExprNode(node) => {
+                let iterable = self.infer(&node.iterable);
+                let element = self.element_type(&iterable, &node.iterable.span());
+
+                self.bind_pattern(&node.pattern, &element);
+                self.check_statements(&node.body);
+                Type::None
+            },
+            Node::FuncDefNode(node) => {
+                self.check_function(node);
+                Type::None
+            },
+            Node::FuncCallNode(node) => {
+                let function = self.infer(&node.func_node);
+
+                // where the function was defined, to point at when arguments conflict
+                let defined = match node.func_node {
+                    Node::VarAccessNode(ref access) => self.find(&access.identifier.to_string())
+                        .map(|x| x.position)
+                        .filter(|x| !x.is_internal()),
+                    _ => None,
+                };
+
+                let args: Vec<(Type, TokenPosition)> = node.args.iter().map(|x| (self.infer(x), x.span())).collect();
+                let kwargs: Vec<(String, Type, TokenPosition)> = node.kwargs.iter()
+                    .map(|(name, value)| (name.to_string(), self.infer(value), value.span()))
+                    .collect();
+
+                self.infer_call(&function, &args, &kwargs, &node.func_node.get_position(), defined)
+            },
+            Node::MethodCallNode(node) => self.infer_method_call(node),
+            Node::AttributeAccessNode(node) => {
+                let object = self.infer(&node.object);
+
+                if !matches!(self.resolve(&object), Type::Dynamic | Type::Module | Type::Var(_)) {
+                    let object = self.describe(&[&object])[0].clone();
+                    self.error(format!("Cannot access attribute '{}' of {}", node.attribute, object), &node.attribute.position);
+                }
+
+                Type::Dynamic
+            },
+            Node::ListExprNode(node) => {
+                let element = self.fresh();
+                let mut first: Option<TokenPosition> = None;
+
+                for node in &node.elements {
+                    let value = self.infer(node);
+
+                    if !self.unify(&element, &value) {
+                        let types = self.describe(&[&element, &value]);
+                        self.errors.push(
+                            Error::new_runtime(ErrorType::TypeError, format!("List elements must have the same type: {} and {}", types[0], types[1]), &node.span())
+                                .labelled(types[1].clone())
+                                .with_label(&first.unwrap_or(node.span()), types[0].clone())
+                        );
+                    }
+
+                    first.get_or_insert(node.span());
+                }
+
+                Type::List(Box::new(element))
+            },
+            Node::ListComprehensionNode(node) => {
+                let iterable = self.infer(&node.iterable);
+                let item = self.element_type(&iterable, &node.iterable.span());
+
+                self.scopes.push(HashMap::new());
+                self.bind_pattern(&node.pattern, &item);
+
+                if let Some(ref condition) = node.condition {
+                    let condition_type = self.infer(condition);
+                    self.expect(&Type::Integer, &condition_type, &condition.span(), "List comprehension condition must be an integer, found");
+                }
+                let element = self.infer(&node.element);
+                self.scopes.pop();
+
+                Type::List(Box::new(element))
+            },
+            Node::TupleExprNode(node) => Type::Tuple(node.elements.iter().map(|x| self.infer(x)).collect()),
+            Node::ReturnNode(node) => {
+                let (value, position) = match node.value {
+                    Some(ref value) => (self.infer(value), value.span()),
+                    None => (Type::None, TokenPosition::internal()),
+                };
+                self.check_return(&value, &position);
+                Type::None
+            },
+            Node::YieldNode(node) => {
+                let value = self.infer(&node.value);
+
+                if let Some((name, Some(yields))) = self.functions.last().map(|x| (x.name.clone(), x.yields.clone())) {
+                    if !self.unify(&yields, &value) {
+                        let types = self.describe(&[&yields, &value]);
+                        self.error(format!("Generator '{}' should yield {}, found {}", name, types[0], types[1]), &node.value.span());
+                    }
+                }
+
+                Type::None
+            },
+            Node::AssertNode(node) => {
+                let condition = self.infer(&node.condition);
+                self.expect(&Type::Integer, &condition, &node.condition.span(), "Assertion condition must be an integer, found");
+                Type::None
+            },
+            Node::TryNode(node) => {
+                let value = self.infer(&node.value);
+
+                // the error is returned from the enclosing function
+                let (inner, propagated) = match self.resolve(&value) {
+                    Type::Result(ok, err) => (*ok, Type::Result(Box::new(self.fresh()), err)),
+                    Type::Option(inner) => (*inner, Type::Option(Box::new(self.fresh()))),
+                    Type::Dynamic | Type::Var(_) => return Type::Dynamic,
+                    other => {
+                        let other = self.describe(&[&other])[0].clone();
+                        self.error(format!("Cannot use ? on {}", other), &node.token.position);
+                        return Type::Dynamic;
+                    },
+                };
+
+                self.check_return(&propagated, &node.token.position);
+                inner
+            },
+            Node::ImportNode(node) => {
+                if node.names.is_empty() {
+                    let (name, position) = match node.alias {
+                        Some(ref alias) => (alias.to_string(), alias.position),
+                        None => (module_name(&node.path.to_string()), node.path.position),
+                    };
+                    self.bind(name, Scheme::mono(Type::Module), position, None);
+                } else {
+                    for name in &node.names {
+                        self.bind(name.to_string(), Scheme::mono(Type::Dynamic), name.position, None);
+                    }
+                }
+                Type::None
+            },
+            Node::ExecuteBuiltinNode(_) | Node::ErrorNode(_) => Type::Dynamic,
+            Node::StatementsNode(_) => {
+                self.check_statements(node);
+                Type::None
+            },
+        }
+    }
+
+    /// Type of the result of a binary operation, reporting unsupported operands
+    fn infer_binary_operation(&mut self, op_token: &Token, left: (&Type, &Node), right: (&Type, &Node)) -> Type {
+        let (verb, result) = match op_token.value {
+            TokenType::Plus | TokenType::PlusEqual => ("add", self.arithmetic(left.0, right.0)),
+            TokenType::Minus | TokenType::MinusEqual => ("subtract", self.arithmetic(left.0, right.0)),
+            TokenType::Star | TokenType::StarEqual => ("multiply", self.arithmetic(left.0, right.0)),
+            TokenType::Slash | TokenType::SlashEqual => ("divide", self.arithmetic(left.0, right.0)),
+            TokenType::Caret => ("raise", self.arithmetic(left.0, right.0)),
+            TokenType::Less | TokenType::LessEqual |
+            TokenType::Greater | TokenType::GreaterEqual => ("compare", self.arithmetic(left.0, right.0).map(|_| Type::Integer)),
+            TokenType::EqualEqual | TokenType::BangEqual => ("compare", self.equality(left.0, right.0)),
+            TokenType::Keyword(Keyword::And) | TokenType::Keyword(Keyword::Or) => {
+                let integers = self.unify(&Type::Integer, left.0) && self.unify(&Type::Integer, right.0);
+                ("combine", if integers { Some(Type::Integer) } else { None })
+            },
+            _ => return Type::Dynamic,
+        };
+
+        match result {
+            Some(result) => result,
+            None => {
+                let types = self.describe(&[left.0, right.0]);
+                self.errors.push(
+                    Error::new_runtime(ErrorType::TypeError, format!("Cannot {} {} and {}", verb, types[0], types[1]), &op_token.position)
+                        .with_label(&self.origin(left.1), types[0].clone())
+                        .with_label(&self.origin(right.1), types[1].clone())
+                );
+                Type::Dynamic
+            },
+        }
+    }
+
+    /// Check a value can be looked for in a container with `in`
+    fn check_membership(&mut self, element: &Type, container: &Type, position: &TokenPosition) {
+        let accepted = match self.resolve(container) {
+            Type::String => self.unify(&Type::String, element),
+            Type::List(inner) | Type::Iterator(inner) => self.unify(&inner, element),
+            Type::Tuple(_) | Type::Dynamic | Type::Var(_) => true,
+            _ => false,
+        };
+
+        if !accepted {
+            let types = self.describe(&[element, container]);
+            self.error(format!("Cannot check for {} in {}", types[0], types[1]), position);
+        }
+    }
+
+    /// Type of the result of an arithmetic operation, if the operands are numbers
+    fn arithmetic(&mut self, left: &Type, right: &Type) -> Option<Type> {
+        if !self.constrain_numeric(left) || !self.constrain_numeric(right) {
+            return None;
+        }
+
+        Some(match (self.resolve(left), self.resolve(right)) {
+            (Type::Integer, Type::Integer) => Type::Integer,
+            (Type::Float, _) | (_, Type::Float) => Type::Float,
+            (Type::Dynamic, _) | (_, Type::Dynamic) => Type::Dynamic,
+            // either could be an integer or a float, so the result could be either
+            (Type::Var(a), Type::Var(b)) if a != b => {
+                let result = self.fresh();
+                self.constrain_numeric(&result);
+                result
+            },
+            (Type::Var(_), _) => left.clone(),
+            _ => right.clone(),
+        })
+    }
+
+    /// Type of the result of comparing two values for equality, if they can be compared
+    fn equality(&mut self, left: &Type, right: &Type) -> Option<Type> {
+        match (self.resolve(left), self.resolve(right)) {
+            (Type::String, Type::String) => Some(Type::Integer),
+            (a, b) if a.is_numeric() || b.is_numeric() => self.arithmetic(left, right).map(|_| Type::Integer),
+            // containers are compared element by element
+            (Type::List(a), Type::List(b)) |
+            (Type::Option(a), Type::Option(b)) => self.equality(&a, &b),
+            (Type::Result(a, x), Type::Result(b, y)) => self.equality(&a, &b).and(self.equality(&x, &y)),
+            (Type::Tuple(a), Type::Tuple(b)) if a.len() == b.len() => {
+                let comparable = a.iter().zip(&b).all(|(a, b)| self.equality(a, b).is_some());
+                comparable.then_some(Type::Integer)
+            },
+            (Type::Var(_) | Type::Dynamic, Type::Var(_) | Type::Dynamic | Type::String | Type::List(_) | Type::Tuple(_) | Type::Option(_) | Type::Result(..)) |
+            (Type::String | Type::List(_) | Type::Tuple(_) | Type::Option(_) | Type::Result(..), Type::Var(_) | Type::Dynamic) => match self.unify(left, right) {
+                true => Some(Type::Integer),
+                false => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Check the arguments of a call and return the type of its result
+    fn infer_call(&mut self, function: &Type, args: &[(Type, TokenPosition)], kwargs: &[(String, Type, TokenPosition)], position: &TokenPosition, defined: Option<TokenPosition>) -> Type {
+        let signature = match self.resolve(function) {
+            Type::Function(signature) => signature,
+            Type::Var(_) => {
+                // calling an unknown value decides its type
+                let returns = self.fresh();
+                let parameters = args.iter().map(|(x, _)| (String::new(), x.clone())).collect();
+                if !self.unify(function, &Type::function(parameters, None, returns.clone())) {
+                    // a number, or a function which would have to be an argument of itself
+                    let message = match self.resolve(function) {
+                        Type::Var(var) if !self.numeric.contains(&var) => "Cannot pass a function to itself".to_string(),
+                        _ => format!("Cannot call {}", self.describe(&[function])[0]),
+                    };
+                    self.error(message, position);
+                }
+                return returns;
+            },
+            Type::Dynamic => return Type::Dynamic,
+            other => {
+                let other = self.describe(&[&other])[0].clone();
+                self.error(format!("Cannot call {}", other), position);
+                return Type::Dynamic;
+            },
+        };
+
+        let mut parameters = signature.parameters.iter();
+        for (index, (value, position)) in args.iter().enumerate() {
+            let (name, expected) = match parameters.next() {
+                Some((name, expected)) => (name.as_str(), expected),
+                None => match signature.variadic {
+                    Some(ref expected) => ("", expected),
+                    // too many arguments are reported by the resolver
+                    None => break,
+                },
+            };
+
+            // values which are called have no parameter names, nor do the values collected by `*`
+            let argument = match name {
+                "" => format!("argument {}", index + 1),
+                name => format!("argument '{}'", name),
+            };
+
+            self.expect_argument(&argument, expected, value, position, defined);
+        }
+
+        for (name, value, position) in kwargs {
+            if let Some((_, expected)) = signature.parameters.iter().find(|(x, _)| x == name) {
+                self.expect_argument(&format!("argument '{}'", name), expected, value, position, defined);
+            }
+        }
+
+        signature.returns.clone()
+    }
+
+    /// Check an argument against its parameter, pointing out where the function
+    /// was defined if it is known
+    fn expect_argument(&mut self, argument: &str, expected: &Type, value: &Type, position: &TokenPosition, defined: Option<TokenPosition>) {
+        // lists and tuples can be given where an iterator is expected
+        let accepted = match (self.resolve(expected), self.resolve(value)) {
+            (Type::Iterator(element), Type::List(value)) => self.unify(&element, &value),
+            (Type::Iterator(_), Type::Tuple(_)) => true,
+            _ => self.accepts(expected, value),
+        };
+
+        if !accepted {
+            let types = self.describe(&[expected, value]);
+            let mut error = Error::new_runtime(ErrorType::TypeError, format!("Expected {} of type {}, found {}", argument, types[0], types[1]), position);
+            if let Some(ref defined) = defined {
+                error = error.with_label(defined, "function defined here".to_string());
+            }
+            self.errors.push(error);
+        }
+    }
+
+    /// Methods of results and options
+    fn infer_method_call(&mut self, node: &MethodCallNode) -> Type {
+        let object = self.infer(&node.object);
+        let args: Vec<(Type, TokenPosition)> = node.args.iter().map(|x| (self.infer(x), x.span())).collect();
+        for (_, value) in &node.kwargs {
+            self.infer(value);
+        }
+
+        let method = node.method.to_string();
+
+        // the inner value, and how to rebuild the container around a new inner value
+        let (inner, rebuild): (Type, Box<dyn Fn(Type) -> Type>) = match self.resolve(&object) {
+            Type::Result(ok, err) => (*ok, Box::new(move |x| Type::Result(Box::new(x), err.clone()))),
+            Type::Option(inner) => (*inner, Box::new(|x| Type::Option(Box::new(x)))),
+            Type::Dynamic | Type::Module | Type::Var(_) => return Type::Dynamic,
+            other => {
+                let other = self.describe(&[&other])[0].clone();
+                self.error(format!("Cannot call method '{}' on {}", method, other), &node.method.position);
+                return Type::Dynamic;
+            },
+        };
+
+        match method.as_str() {
+            "is_ok" | "is_err" | "is_some" | "is_none" => Type::Integer,
+            "unwrap" => inner,
+            "unwrap_or" => {
+                if let Some((default, position)) = args.first() {
+                    self.expect_argument("argument 'default'", &inner, default, position, None);
+                }
+                inner
+            },
+            "map" => {
+                let returns = self.fresh();
+                if let Some((function, position)) = args.first() {
+                    let expected = Type::function(vec![("value".to_string(), inner)], None, returns.clone());
+                    self.expect_argument("argument 'function'", &expected, function, position, None);
+                }
+                rebuild(returns)
+            },
+            _ => {
+                let object = self.describe(&[&object])[0].clone();
+                let error = Error::new_runtime(ErrorType::TypeError, format!("Unknown method '{}' for {}", method, object), &node.method.position)
+                    .with_suggestions(&method, RESULT_METHODS);
+                self.errors.push(error);
+                Type::Dynamic
+            },
+        }
+    }
+
+    /// Type of the elements of a value which is iterated over
+    fn element_type(&mut self, iterable: &Type, position: &TokenPosition) -> Type {
+        match self.resolve(iterable) {
+            Type::List(element) | Type::Iterator(element) => *element,
+            Type::Dynamic | Type::Tuple(_) => Type::Dynamic,
+            // anything iterable is accepted where an iterator is expected
+            Type::Var(_) => {
+                let element = self.fresh();
+                self.unify(iterable, &Type::Iterator(Box::new(element.clone())));
+                element
+            },
+            other => {
+                let other = self.describe(&[&other])[0].clone();
+                self.error(format!("Cannot iterate over {}", other), position);
+                Type::Dynamic
+            },
+        }
+    }
+
+    /// Check a function definition and declare it with its most general type
+    fn check_function(&mut self, node: &FuncDefNode) {
+        let name = node.identifier.to_string();
+        let mut parameters: Vec<(String, Type)> = Vec::new();
+        let mut variadic: Option<Type> = None;
+
+        for parameter in &node.parameters {
+            let expected = match parameter.annotation {
+                Some(ref annotation) => self.annotation(annotation),
+                None => self.fresh(),
+            };
+
+            if let Some(ref default) = parameter.default {
+                let value = self.infer(default);
+                let message = format!("Default of parameter '{}' should be {}, found", parameter.identifier, self.describe(&[&expected])[0]);
+                self.expect(&expected, &value, &default.span(), &message);
+            }
+
+            let parameter_name = match parameter.pattern {
+                Some(ref pattern) => pattern.to_string(),
+                None => parameter.identifier.to_string(),
+            };
+
+            match parameter.variadic {
+                true => variadic = Some(expected),
+                false => parameters.push((parameter_name, expected)),
+            }
+        }
+
+        let returns = match node.return_type {
+            Some(ref annotation) => self.annotation(annotation),
+            None => self.fresh(),
+        };
+
+        // generators return an iterator over the values they yield
+        let yields = match node.body.as_ref().is_some_and(contains_yield) {
+            true => {
+                let yields = self.fresh();
+                let iterator = Type::Iterator(Box::new(yields.clone()));
+
+                if !self.unify(&returns, &iterator) {
+                    let types = self.describe(&[&returns, &iterator]);
+                    self.error(format!("Function '{}' is a generator, so returns {} rather than {}", name, types[1], types[0]), &node.identifier.position);
+                }
+
+                Some(yields)
+            },
+            false => None,
+        };
+
+        let function = Type::function(parameters.clone(), variadic.clone(), returns.clone());
+
+        // the function is not generic within its own body
+        self.bind(name.clone(), Scheme::mono(function.clone()), node.identifier.position, None);
+
+        self.scopes.push(HashMap::new());
+        for (parameter, (_, expected)) in node.parameters.iter().filter(|x| !x.variadic).zip(&parameters) {
+            match parameter.pattern {
+                Some(ref pattern) => self.bind_pattern(pattern, expected),
+                None => {
+                    let declared = parameter.annotation.as_ref().map(|_| expected.clone());
+                    self.bind(parameter.identifier.to_string(), Scheme::mono(expected.clone()), parameter.identifier.position, declared);
+                },
+            }
+        }
+        if let (Some(parameter), Some(element)) = (node.parameters.iter().find(|x| x.variadic), variadic) {
+            self.bind(parameter.identifier.to_string(), Scheme::mono(Type::List(Box::new(element))), parameter.identifier.position, None);
+        }
+
+        self.functions.push(FunctionContext {
+            name: name.clone(),
+            returns: returns.clone(),
+            origin: node.return_type.as_ref().map(|x| x.position),
+            yields,
+        });
+        if let Some(ref body) = node.body {
+            self.check_statements(body);
+        }
+        let context = self.functions.pop().expect("Function context was not pushed");
+        self.scopes.pop();
+
+        // functions without a return statement return none
+        if context.origin.is_none() && context.yields.is_none() {
+            self.unify(&returns, &Type::None);
+        }
+
+        self.current_scope().remove(&name);
+        let scheme = self.generalize(&function);
+        self.bind(name, scheme, node.identifier.position, None);
+    }
+
+    /// Check a value returned from the current function
+    fn check_return(&mut self, value: &Type, position: &TokenPosition) {
+        let (name, returns, origin) = match self.functions.last() {
+            // returning only finishes a generator, so the value is not used
+            Some(context) if context.yields.is_some() => return,
+            Some(context) => (context.name.clone(), context.returns.clone(), context.origin),
+            None => return,
+        };
+
+        if !self.accepts(&returns, value) {
+            let types = self.describe(&[&returns, value]);
+            let mut error = Error::new_runtime(ErrorType::TypeError, format!("Function '{}' should return {}, found {}", name, types[0], types[1]), position);
+            if let Some(ref origin) = origin {
+                error = error.with_label(origin, format!("{} returned here", types[0]));
+            }
+            self.errors.push(error);
+        }
+
+        if let Some(context) = self.functions.last_mut() {
+            context.origin.get_or_insert(*position);
+        }
+    }
+
+    /// Bind the names of a pattern to the types of the values destructured into them
+    fn bind_pattern(&mut self, pattern: &Pattern, value: &Type) {
+        let (patterns, types) = match (pattern, self.resolve(value)) {
+            (Pattern::Identifier(identifier), _) => {
+                self.assign(identifier, value.clone(), None, &identifier.position);
+                return;
+            },
+            (Pattern::Tuple(patterns, _), Type::Tuple(types)) => {
+                if types.len() != patterns.len() {
+                    self.errors.push(Error::new_runtime(
+                        ErrorType::ArgumentError,
+                        format!("Expected {} to destructure into {}, got {}", errors::count(patterns.len(), "value"), pattern, types.len()),
+                        &pattern.get_position()
+                    ));
+                }
+                (patterns, types)
+            },
+            (Pattern::Tuple(patterns, _), Type::Var(_)) => {
+                let types: Vec<Type> = patterns.iter().map(|_| self.fresh()).collect();
+                self.unify(value, &Type::Tuple(types.clone()));
+                (patterns, types)
+            },
+            (Pattern::List(patterns, _), Type::List(element)) => (patterns, vec![*element; patterns.len()]),
+            (Pattern::List(patterns, _), Type::Var(_)) => {
+                let element = self.fresh();
+                self.unify(value, &Type::List(Box::new(element.clone())));
+                (patterns, vec![element; patterns.len()])
+            },
+            (Pattern::Tuple(patterns, _) | Pattern::List(patterns, _), other) => {
+                if other != Type::Dynamic {
+                    let other = self.describe(&[&other])[0].clone();
+                    self.error(format!("Cannot destructure {} into {}", other, pattern), &pattern.get_position());
+                }
+                (patterns, Vec::new())
+            },
+        };
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            let value = types.get(index).cloned().unwrap_or(Type::Dynamic);
+            self.bind_pattern(pattern, &value);
+        }
+    }
+
+    /// Assign a value to a variable, checking it against any declared type.
+    /// The variable is generic over the type variables only its value uses.
+    fn assign(&mut self, identifier: &Token, value: Type, annotation: Option<Type>, position: &TokenPosition) {
+        let name = identifier.to_string();
+
+        // a declared type only applies within the scope it was declared in
+        let previous = match annotation {
+            Some(_) => None,
+            None => self.current_scope().get(&name).filter(|x| x.declared.is_some()).cloned(),
+        };
+
+        let (declared, declared_at) = match (annotation, previous) {
+            (Some(annotation), _) => (Some(annotation), identifier.position),
+            (None, Some(previous)) => (previous.declared, previous.position),
+            (None, None) => (None, identifier.position),
+        };
+
+        if let Some(ref declared) = declared {
+            if !self.accepts(declared, &value) {
+                let types = self.describe(&[&value, declared]);
+                self.errors.push(
+                    Error::new_runtime(ErrorType::TypeError, format!("Cannot assign {} to '{}' declared as {}", types[0], name, types[1]), position)
+                        .with_label(&declared_at, format!("declared as {}", types[1]))
+                );
+            }
+        }
+
+        let ty = declared.clone().unwrap_or(value);
+        self.current_scope().remove(&name);
+        let scheme = self.generalize(&ty);
+        self.bind(name, scheme, declared_at, declared);
+    }
+
+    fn bind(&mut self, name: String, scheme: Scheme, position: TokenPosition, declared: Option<Type>) {
+        let mut free: Vec<u32> = Vec::new();
+        scheme.ty.free_vars(&mut free);
+        free.retain(|x| !scheme.vars.contains(x));
+
+        self.current_scope().insert(name, Binding { scheme, position, declared, free });
+    }
+
+    /// Type of a variable, or `any` if it is not known
+    fn lookup(&mut self, identifier: &Token) -> Type {
+        match self.find(&identifier.to_string()) {
+            Some(binding) => self.instantiate(&binding.scheme),
+            None => Type::Dynamic,
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    /// Where the type of a node's value comes from
+    fn origin(&self, node: &Node) -> TokenPosition {
+        match node {
+            Node::VarAccessNode(access) => match self.find(&access.identifier.to_string()) {
+                Some(binding) if !binding.position.is_internal() => binding.position,
+                _ => node.get_position(),
+            },
+            _ => node.get_position(),
+        }
+    }
+
+    fn current_scope(&mut self) -> &mut HashMap<String, Binding> {
+        self.scopes.last_mut().expect("Checker has no scope")
+    }
+
+    /// Type named by an annotation
+    fn annotation(&mut self, token: &Token) -> Type {
+        match token.to_string().as_str() {
+            "int" => Type::Integer,
+            "float" => Type::Float,
+            "string" => Type::String,
+            "none" => Type::None,
+            "module" => Type::Module,
+            "list" => Type::List(Box::new(self.fresh())),
+            "result" => Type::Result(Box::new(self.fresh()), Box::new(self.fresh())),
+            "option" => Type::Option(Box::new(self.fresh())),
+            "iterator" => Type::Iterator(Box::new(self.fresh())),
+            "any" | "tuple" | "function" => Type::Dynamic,
+            _ => {
+                self.error(format!("Unknown type '{}'", token), &token.position);
+                Type::Dynamic
+            },
+        }
+    }
+
+    /// Type of a built-in function, from its signature
+    fn builtin_type(&mut self, name: &str, function: &FunctionSymbol) -> Type {
+        let value = self.fresh();
+        let error = self.fresh();
+
+        match name {
+            "Ok" => return Type::function(vec![("value".to_string(), value.clone())], None, Type::Result(Box::new(value), Box::new(error))),
+            "Err" => return Type::function(vec![("error".to_string(), error.clone())], None, Type::Result(Box::new(value), Box::new(error))),
+            "Some" => return Type::function(vec![("value".to_string(), value.clone())], None, Type::Option(Box::new(value))),
+            _ => {},
+        }
+
+        // functions over iterables are generic over their elements
+        let element = self.fresh();
+        let list = |x: &Type| Type::List(Box::new(x.clone()));
+        let iterator = |x: &Type| Type::Iterator(Box::new(x.clone()));
+        let unary = |returns: &Type| Type::function(vec![("element".to_string(), element.clone())], None, returns.clone());
+        let parameter = |name: &str, ty: Type| (name.to_string(), ty);
+
+        match name {
+            "map" => return Type::function(
+                vec![parameter("iterable", iterator(&element)), parameter("function", unary(&value))], None, list(&value)
+            ),
+            "filter" => return Type::function(
+                vec![parameter("iterable", iterator(&element)), parameter("function", unary(&Type::Integer))], None, list(&element)
+            ),
+            "reduce" => return Type::function(
+                vec![
+                    parameter("iterable", iterator(&element)),
+                    parameter("function", Type::function(vec![parameter("accumulated", value.clone()), parameter("element", element.clone())], None, value.clone())),
+                    parameter("initial", value.clone()),
+                ],
+                None,
+                value
+            ),
+            "any" | "all" => return Type::function(vec![parameter("iterable", iterator(&element))], None, Type::Integer),
+            "enumerate" => return Type::function(
+                vec![parameter("iterable", iterator(&element))], None, list(&Type::Tuple(vec![Type::Integer, element.clone()]))
+            ),
+            "sorted" => return Type::function(
+                vec![parameter("iterable", iterator(&element)), parameter("key", unary(&value))], None, list(&element)
+            ),
+            "range" => return Type::function(
+                vec![parameter("start", Type::Integer), parameter("stop", Type::Integer), parameter("step", Type::Integer)], None, iterator(&Type::Integer)
+            ),
+            "take" => return Type::function(
+                vec![parameter("iterable", iterator(&element)), parameter("count", Type::Integer)], None, iterator(&element)
+            ),
+            "chain" => return Type::function(Vec::new(), Some(iterator(&element)), iterator(&element)),
+            _ => {},
+        }
+
+        let expected = |parameter_type: ParameterType| match parameter_type {
+            ParameterType::Integer => Type::Integer,
+            ParameterType::Float => Type::Float,
+            ParameterType::String => Type::String,
+            ParameterType::List => Type::List(Box::new(Type::Dynamic)),
+            ParameterType::Iterable | ParameterType::Iterator => Type::Iterator(Box::new(Type::Dynamic)),
+            ParameterType::Result => Type::Result(Box::new(Type::Dynamic), Box::new(Type::Dynamic)),
+            ParameterType::Option => Type::Option(Box::new(Type::Dynamic)),
+            ParameterType::Module => Type::Module,
+            ParameterType::None => Type::None,
+            ParameterType::Any | ParameterType::Number | ParameterType::Tuple | ParameterType::Function => Type::Dynamic,
+        };
+
+        let returns = match name {
+            "print" => Type::None,
+            "input" => Type::String,
+            "int" => Type::Integer,
+            "float" => Type::Float,
+            _ => Type::Dynamic,
+        };
+
+        Type::function(
+            function.parameters.iter().filter(|x| !x.variadic).map(|x| (x.name.clone(), expected(x.expected))).collect(),
+            function.parameters.iter().find(|x| x.variadic).map(|x| expected(x.expected)),
+            returns
+        )
+    }
+
+    // ================ Unification ================
+
+    fn fresh(&mut self) -> Type {
+        self.next_var += 1;
+        Type::Var(self.next_var - 1)
+    }
+
+    /// Follow solved type variables until a type which is not solved
+    fn resolve(&self, ty: &Type) -> Type {
+        let mut ty = ty.clone();
+
+        while let Type::Var(var) = ty {
+            match self.substitution.get(&var) {
+                Some(solved) => ty = solved.clone(),
+                None => break,
+            }
+        }
+
+        ty
+    }
+
+    /// Apply every solved type variable within a type
+    fn resolve_deep(&self, ty: &Type) -> Type {
+        let ty = self.resolve(ty);
+        let mut vars: Vec<u32> = Vec::new();
+        ty.free_vars(&mut vars);
+
+        let solved: HashMap<u32, Type> = vars.into_iter()
+            .filter(|x| self.substitution.contains_key(x))
+            .map(|x| (x, self.resolve_deep(&Type::Var(x))))
+            .collect();
+
+        ty.substitute(&solved)
+    }
+
+    /// Write types for an error message. Unsolved variables used in arithmetic are numbers.
+    fn describe(&self, types: &[&Type]) -> Vec<String> {
+        let resolved: Vec<Type> = types.iter().map(|x| self.resolve_deep(x)).collect();
+        describe(&resolved.iter().collect::<Vec<&Type>>(), &self.numeric)
+    }
+
+    /// Make two types equal, solving type variables. Returns false if they conflict.
+    fn unify(&mut self, a: &Type, b: &Type) -> bool {
+        match (self.resolve(a), self.resolve(b)) {
+            (Type::Dynamic, _) | (_, Type::Dynamic) => true,
+            (Type::Var(a), Type::Var(b)) if a == b => true,
+            (Type::Var(var), other) | (other, Type::Var(var)) => self.solve(var, other),
+            (Type::Integer, Type::Integer) | (Type::Float, Type::Float) => true,
+            (Type::String, Type::String) | (Type::None, Type::None) | (Type::Module, Type::Module) => true,
+            (Type::List(a), Type::List(b)) | (Type::Option(a), Type::Option(b)) |
+            (Type::Iterator(a), Type::Iterator(b)) => self.unify(&a, &b),
+            (Type::Result(a, e), Type::Result(b, f)) => self.unify(&a, &b) && self.unify(&e, &f),
+            (Type::Tuple(a), Type::Tuple(b)) => a.len() == b.len() && a.iter().zip(&b).all(|(a, b)| self.unify(a, b)),
+            (Type::Function(a), Type::Function(b)) => self.unify_functions(&a, &b),
+            _ => false,
+        }
+    }
+
+    /// Whether a value can be given where a type is expected. Integers are
+    /// widened to floats, but floats are never narrowed to integers.
+    fn accepts(&mut self, expected: &Type, value: &Type) -> bool {
+        match (self.resolve(expected), self.resolve(value)) {
+            (Type::Float, Type::Integer) => true,
+            _ => self.unify(expected, value),
+        }
+    }
+
+    fn unify_functions(&mut self, a: &FunctionType, b: &FunctionType) -> bool {
+        if a.parameters.len() != b.parameters.len() {
+            return false;
+        }
+
+        let parameters = a.parameters.iter().zip(&b.parameters).all(|((_, a), (_, b))| self.unify(a, b));
+        let variadic = match (&a.variadic, &b.variadic) {
+            (Some(a), Some(b)) => self.unify(a, b),
+            _ => true,
+        };
+
+        parameters && variadic && self.unify(&a.returns, &b.returns)
+    }
+
+    /// Solve a type variable
+    fn solve(&mut self, var: u32, ty: Type) -> bool {
+        if let Type::Var(other) = ty {
+            if self.numeric.contains(&var) {
+                self.numeric.insert(other);
+            }
+            self.substitution.insert(var, ty);
+            return true;
+        }
+
+        // a type cannot contain itself
+        let mut vars: Vec<u32> = Vec::new();
+        self.resolve_deep(&ty).free_vars(&mut vars);
+        if vars.contains(&var) {
+            return false;
+        }
+
+        if self.numeric.contains(&var) && !ty.is_numeric() {
+            return false;
+        }
+
+        self.substitution.insert(var, ty);
+        true
+    }
+
+    /// Require a type to be a number. Returns false if it cannot be.
+    fn constrain_numeric(&mut self, ty: &Type) -> bool {
+        match self.resolve(ty) {
+            Type::Var(var) => {
+                self.numeric.insert(var);
+                true
+            },
+            other => other.is_numeric() || other == Type::Dynamic,
+        }
+    }
+
+    /// Make a type generic over the type variables which no variable in scope uses
+    fn generalize(&self, ty: &Type) -> Scheme {
+        let ty = self.resolve_deep(ty);
+
+        let mut vars: Vec<u32> = Vec::new();
+        ty.free_vars(&mut vars);
+        if vars.is_empty() {
+            return Scheme::mono(ty);
+        }
+
+        // only the free variables of a binding can have been solved to types using others
+        let mut in_scope: Vec<u32> = Vec::new();
+        for binding in self.scopes.iter().flat_map(|x| x.values()).filter(|x| !x.free.is_empty()) {
+            for var in &binding.free {
+                self.resolve_deep(&Type::Var(*var)).free_vars(&mut in_scope);
+            }
+        }
+
+        let in_scope: HashSet<u32> = in_scope.into_iter().collect();
+        vars.retain(|x| !in_scope.contains(x));
+
+        Scheme { vars, ty }
+    }
+
+    /// Give a generic type fresh type variables
+    fn instantiate(&mut self, scheme: &Scheme) -> Type {
+        let mut fresh: HashMap<u32, Type> = HashMap::new();
+
+        for var in &scheme.vars {
+            let new = self.fresh();
+            if let (true, Type::Var(new)) = (self.numeric.contains(var), &new) {
+                self.numeric.insert(*new);
+            }
+            fresh.insert(*var, new);
+        }
+
+        scheme.ty.substitute(&fresh)
+    }
+
+    /// Report an error if a value does not have an expected type
+    fn expect(&mut self, expected: &Type, value: &Type, position: &TokenPosition, message: &str) {
+        if !self.accepts(expected, value) {
+            let value = self.describe(&[value])[0].clone();
+            self.error(format!("{} {}", message, value), position);
+        }
+    }
+
+    fn error(&mut self, message: String, position: &TokenPosition) {
+        self.errors.push(Error::new_runtime(ErrorType::TypeError, message, position));
+    }
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn check_source(text: &str) -> Result<Signatures, Vec<Error>> {
+        let tokens = Lexer::new(text.to_string()).make_tokens().unwrap();
+        check(&Parser::new(tokens).parse().unwrap())
+    }
+
+    /// Every `.yum` file in a directory, with its text
+    fn sources(directory: &str) -> Vec<(String, String)> {
+        let mut paths: Vec<_> = std::fs::read_dir(directory).unwrap()
+            .map(|x| x.unwrap().path())
+            .filter(|x| x.extension().is_some_and(|x| x == "yum"))
+            .collect();
+        paths.sort();
+
+        paths.into_iter().map(|x| (x.display().to_string(), std::fs::read_to_string(&x).unwrap())).collect()
+    }
+
+    #[test]
+    fn examples_have_no_type_errors() {
+        for (path, text) in sources("test_code") {
+            assert!(check_source(&text).is_ok(), "{}: {:?}", path, check_source(&text));
+        }
+    }
+
+    #[test]
+    fn type_error_examples_are_rejected() {
+        let examples = sources("test_code/type_errors");
+        assert!(!examples.is_empty());
+
+        for (path, text) in examples {
+            let errors = check_source(&text).unwrap_err();
+            assert!(errors.iter().all(|x| matches!(x.error_type, ErrorType::TypeError)), "{}: {:?}", path, errors);
+        }
+    }
+
+    #[test]
+    fn integers_widen_to_floats_but_floats_do_not_narrow() {
+        assert!(check_source("let a: float = 1; func f(x: float) -> float { return 2; }; f(3);").is_ok());
+        assert!(check_source("let xs = [1.0, 2.5]; let y: float = if 1 { 1.0 } else { 2.5 };").is_ok());
+        assert!(check_source("func add(a, b) { return a + b; }; let x = add(1, 2.5); let n: int = add(1, 2);").is_ok());
+
+        assert!(check_source("let a: int = 2.5;").is_err());
+        assert!(check_source("func f(w: int) { return w; }; f(2.5);").is_err());
+        assert!(check_source("func f() -> int { return 2.5; };").is_err());
+        // branches are not converted at runtime, so they cannot be widened
+        assert!(check_source("let xs = [1, 2.5];").is_err());
+        assert!(check_source("let y: float = if 1 { 1 } else { 2.5 };").is_err());
+    }
+
+    #[test]
+    fn errors_name_arguments_without_type_variables() {
+        let examples = [
+            ("func g(f) { return (f(1), f(\"a\")); };", "Expected argument 1 of type int, found string"),
+            ("func f(*xs) { return xs; }; f(1, \"a\");", "Expected argument 2 of type int, found string"),
+            ("func f(a: int) { return a; }; f(a: \"a\");", "Expected argument 'a' of type int, found string"),
+            ("func g(x) -> int { return x; }; g(Err(\"a\"));", "Expected argument 'x' of type int, found result<any, string>"),
+            ("func g(f) { return f(f); };", "Cannot pass a function to itself"),
+            ("func g(x) { let y = x + 1; return x(1); };", "Cannot call number"),
+        ];
+
+        for (source, message) in examples {
+            let errors = check_source(source).unwrap_err();
+            assert_eq!(errors[0].error_message, message, "{}", source);
+        }
+    }
+
+    #[test]
+    fn piped_values_are_the_first_argument_of_builtins() {
+        let definitions = "func parse(text) { return [1, 7, 9]; }; func is_valid(n) { return n > 5; }; func add(a, b) { return a + b; }; let x = \"abc\";";
+
+        assert!(check_source(&format!("{} let valid: list = x |> parse |> filter(is_valid) |> sorted;", definitions)).is_ok());
+        assert!(check_source(&format!("{} let total: int = x |> parse |> reduce(add, 0);", definitions)).is_ok());
+        assert!(check_source(&format!("{} let total: string = x |> parse |> reduce(add, 0);", definitions)).is_err());
+    }
+
+    #[test]
+    fn values_stay_generic_after_many_bindings() {
+        let lets: String = (0..2000).map(|i| format!("let a{} = {};\n", i, i)).collect();
+        let source = format!("{}func id(x) {{ return x; }}; let n: int = id(1); let s: string = id(\"s\");", lets);
+        assert_eq!(check_source(&source).unwrap(), vec![("id".to_string(), "func(a) -> a".to_string())]);
+    }
+}
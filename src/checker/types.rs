@@ -0,0 +1,170 @@
+//! Types the checker gives to expressions
+
+use std::{collections::{HashMap, HashSet}, rc::Rc};
+
+/// Static type of a value. `Dynamic` is used whenever a type cannot be known
+/// before running, and is compatible with every other type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// Type variable, solved by unification
+    Var(u32),
+    Dynamic,
+    Integer,
+    Float,
+    String,
+    None,
+    Module,
+    /// List with the type of its elements
+    List(Box<Type>),
+    Tuple(Vec<Type>),
+    /// Result with the types of its `Ok` and `Err` values
+    Result(Box<Type>, Box<Type>),
+    Option(Box<Type>),
+    /// Lazy iterator with the type of the values it produces
+    Iterator(Box<Type>),
+    Function(Rc<FunctionType>),
+}
+
+/// Signature of a function
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionType {
+    pub parameters: Vec<(String, Type)>,
+    /// Type of every value collected by a variadic parameter
+    pub variadic: Option<Type>,
+    pub returns: Type,
+}
+
+/// Type which is generic over some of its type variables,
+/// e.g. `func(a) -> a` for an identity function
+#[derive(Debug, Clone)]
+pub struct Scheme {
+    pub vars: Vec<u32>,
+    pub ty: Type,
+}
+
+impl Type {
+    pub fn function(parameters: Vec<(String, Type)>, variadic: Option<Type>, returns: Type) -> Type {
+        Type::Function(Rc::new(FunctionType { parameters, variadic, returns }))
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Integer | Type::Float)
+    }
+
+    /// Type variables used by the type, in order of appearance
+    pub fn free_vars(&self, vars: &mut Vec<u32>) {
+        match self {
+            Type::Var(var) if !vars.contains(var) => vars.push(*var),
+            Type::List(element) | Type::Option(element) | Type::Iterator(element) => element.free_vars(vars),
+            Type::Result(ok, err) => {
+                ok.free_vars(vars);
+                err.free_vars(vars);
+            },
+            Type::Tuple(elements) => for element in elements {
+                element.free_vars(vars);
+            },
+            Type::Function(function) => {
+                for (_, parameter) in &function.parameters {
+                    parameter.free_vars(vars);
+                }
+                if let Some(ref variadic) = function.variadic {
+                    variadic.free_vars(vars);
+                }
+                function.returns.free_vars(vars);
+            },
+            _ => {},
+        }
+    }
+
+    /// Replace type variables by other types
+    pub fn substitute(&self, types: &HashMap<u32, Type>) -> Type {
+        match self {
+            Type::Var(var) => types.get(var).cloned().unwrap_or(Type::Var(*var)),
+            Type::List(element) => Type::List(Box::new(element.substitute(types))),
+            Type::Option(element) => Type::Option(Box::new(element.substitute(types))),
+            Type::Iterator(element) => Type::Iterator(Box::new(element.substitute(types))),
+            Type::Result(ok, err) => Type::Result(Box::new(ok.substitute(types)), Box::new(err.substitute(types))),
+            Type::Tuple(elements) => Type::Tuple(elements.iter().map(|x| x.substitute(types)).collect()),
+            Type::Function(function) => Type::function(
+                function.parameters.iter().map(|(name, x)| (name.clone(), x.substitute(types))).collect(),
+                function.variadic.as_ref().map(|x| x.substitute(types)),
+                function.returns.substitute(types),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Write the type, with `name` giving the name of each type variable
+    fn write(&self, name: &mut dyn FnMut(u32) -> String) -> String {
+        match self {
+            Type::Var(var) => name(*var),
+            Type::Dynamic => "any".to_string(),
+            Type::Integer => "int".to_string(),
+            Type::Float => "float".to_string(),
+            Type::String => "string".to_string(),
+            Type::None => "none".to_string(),
+            Type::Module => "module".to_string(),
+            Type::List(element) => format!("[{}]", element.write(name)),
+            Type::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|x| x.write(name)).collect();
+                match elements.len() {
+                    1 => format!("({},)", elements[0]),
+                    _ => format!("({})", elements.join(", ")),
+                }
+            },
+            Type::Result(ok, err) => format!("result<{}, {}>", ok.write(name), err.write(name)),
+            Type::Option(element) => format!("option<{}>", element.write(name)),
+            Type::Iterator(element) => format!("iterator<{}>", element.write(name)),
+            Type::Function(function) => {
+                let mut parameters: Vec<String> = function.parameters.iter().map(|(_, x)| x.write(name)).collect();
+                if let Some(ref variadic) = function.variadic {
+                    parameters.push(format!("*{}", variadic.write(name)));
+                }
+                format!("func({}) -> {}", parameters.join(", "), function.returns.write(name))
+            },
+        }
+    }
+}
+
+impl Scheme {
+    /// Scheme which is not generic
+    pub fn mono(ty: Type) -> Scheme {
+        Scheme {
+            vars: Vec::new(),
+            ty,
+        }
+    }
+}
+
+/// Write types for an error message, where type variables are not named:
+/// variables used in arithmetic are numbers, and others could be anything
+pub fn describe(types: &[&Type], numeric: &HashSet<u32>) -> Vec<String> {
+    let mut name = |var| match numeric.contains(&var) {
+        true => "number".to_string(),
+        false => "any".to_string(),
+    };
+    types.iter().map(|x| x.write(&mut name)).collect()
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // type variables are named `a`, `b`, ... in order of appearance
+        let mut names: Vec<u32> = Vec::new();
+        let mut name = |var| {
+            let index = match names.iter().position(|x| *x == var) {
+                Some(index) => index,
+                None => {
+                    names.push(var);
+                    names.len() - 1
+                },
+            };
+
+            match index {
+                0..=25 => ((b'a' + index as u8) as char).to_string(),
+                _ => format!("t{}", index),
+            }
+        };
+
+        write!(f, "{}", self.write(&mut name))
+    }
+}
\ No newline at end of file
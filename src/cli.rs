@@ -0,0 +1,230 @@
+//! Reads the command-line arguments into the command to carry out
+
+use std::io::Read;
+
+use crate::error_format::ErrorFormat;
+use crate::interpreter::DEFAULT_MAX_CALL_DEPTH;
+
+pub const USAGE: &str = "\
+Usage: yum_compiler [options] [command]
+
+Commands:
+    run <file> [args...]    Check and run a program, giving it the arguments as `args`
+    check <file>            Check a program without running it
+    tokens <file>           Print the tokens of a program
+    ast <file>              Print the syntax tree of a program
+    eval -e <code>          Check and run code given as an argument
+    repl                    Start an interactive prompt
+
+A file of `-` reads the program from stdin. Given only a file, it is run.
+Given nothing, an interactive prompt is started.
+
+Options:
+    -h, --help              Print this message
+    -V, --version           Print the version
+    -v, --verbose           Say when a program has run successfully
+    --error-format=<format> Write errors as `human` (the default), `json` or `sarif`
+    --max-call-depth=<n>    Allow at most n function calls within each other (1000 by default)
+
+Exit codes:
+    0                       Success, or the code given to `exit`
+    1                       Error while running, or name and type errors
+    2                       Invalid command-line arguments
+    3                       Syntax error
+    4                       Failed assertion
+    5                       Source code could not be read";
+
+/// Command and options given on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arguments {
+    pub command: Command,
+    /// Whether to say when a program has run successfully
+    pub verbose: bool,
+    pub error_format: ErrorFormat,
+    /// Most function calls a program may make within each other
+    pub max_call_depth: usize,
+}
+
+/// Where the source code of a program comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    File(String),
+    Stdin,
+    /// Code given on the command line
+    Text(String),
+}
+
+/// Command given on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run { source: Source, args: Vec<String> },
+    Check(Source),
+    Tokens(Source),
+    Ast(Source),
+    Repl,
+    Help,
+    Version,
+}
+
+impl Source {
+    fn from(arg: String) -> Source {
+        match arg.as_str() {
+            "-" => Source::Stdin,
+            _ => Source::File(arg),
+        }
+    }
+
+    /// Name to show in errors, and to resolve imports relative to
+    pub fn name(&self) -> &str {
+        match self {
+            Source::File(file) => file,
+            Source::Stdin => "<stdin>",
+            Source::Text(_) => "<eval>",
+        }
+    }
+
+    pub fn read(&self) -> std::io::Result<String> {
+        match self {
+            Source::File(file) => std::fs::read_to_string(file),
+            Source::Stdin => {
+                let mut source = String::new();
+                std::io::stdin().read_to_string(&mut source)?;
+                Ok(source)
+            },
+            Source::Text(code) => Ok(code.clone()),
+        }
+    }
+}
+
+/// Read the command from the arguments, not including the program name.
+/// Returns a message explaining the problem if they are not valid.
+pub fn parse_args(args: Vec<String>) -> Result<Arguments, String> {
+    let mut args = args.into_iter().peekable();
+
+    let mut verbose = false;
+    let mut error_format = ErrorFormat::Human;
+    let mut max_call_depth = DEFAULT_MAX_CALL_DEPTH;
+
+    // options come before the command
+    while let Some(option) = args.next_if(|x| x == "-v" || x == "--verbose" || x.starts_with("--error-format=") || x.starts_with("--max-call-depth=")) {
+        if let Some(name) = option.strip_prefix("--error-format=") {
+            error_format = match ErrorFormat::from(name) {
+                Some(format) => format,
+                None => return Err(format!("Unknown error format `{}`, expected `human`, `json` or `sarif`", name)),
+            };
+        } else if let Some(depth) = option.strip_prefix("--max-call-depth=") {
+            max_call_depth = match depth.parse() {
+                Ok(depth) if depth > 0 => depth,
+                _ => return Err(format!("Invalid call depth `{}`, expected a whole number above 0", depth)),
+            };
+        } else {
+            verbose = true;
+        }
+    }
+
+    let command = match args.next() {
+        Some(command) => command,
+        None => return Ok(Arguments { command: Command::Repl, verbose, error_format, max_call_depth }),
+    };
+
+    let mut source = |command: &str| match args.next() {
+        Some(file) => Ok(Source::from(file)),
+        None => Err(format!("Expected a file for `{}`", command)),
+    };
+
+    let parsed = match command.as_str() {
+        "-h" | "--help" | "help" => Command::Help,
+        "-V" | "--version" => Command::Version,
+        "repl" => Command::Repl,
+        "run" => Command::Run { source: source("run")?, args: Vec::new() },
+        "check" => Command::Check(source("check")?),
+        "tokens" => Command::Tokens(source("tokens")?),
+        "ast" => Command::Ast(source("ast")?),
+        "eval" => {
+            let code = match (args.next(), args.next()) {
+                (Some(flag), Some(code)) if flag == "-e" => code,
+                _ => return Err("Expected code for `eval -e <code>`".to_string()),
+            };
+
+            Command::Run { source: Source::Text(code), args: Vec::new() }
+        },
+        option if option.starts_with('-') && option != "-" => return Err(format!("Unknown option `{}`", option)),
+        // a file on its own is run
+        _ => Command::Run { source: Source::from(command), args: Vec::new() },
+    };
+
+    // remaining arguments are given to the program being run
+    let command = match parsed {
+        Command::Run { source, .. } => Command::Run { source, args: args.collect() },
+        parsed => match args.next() {
+            Some(arg) => return Err(format!("Unexpected argument `{}`", arg)),
+            None => parsed,
+        },
+    };
+
+    Ok(Arguments { command, verbose, error_format, max_call_depth })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Arguments, String> {
+        parse_args(args.iter().map(|x| x.to_string()).collect())
+    }
+
+    fn command(args: &[&str]) -> Command {
+        parse(args).unwrap().command
+    }
+
+    fn file(name: &str) -> Source {
+        Source::File(name.to_string())
+    }
+
+    #[test]
+    fn no_arguments_start_the_repl() {
+        let arguments = parse(&[]).unwrap();
+        assert_eq!(arguments, Arguments { command: Command::Repl, verbose: false, error_format: ErrorFormat::Human, max_call_depth: DEFAULT_MAX_CALL_DEPTH });
+        assert_eq!(command(&["repl"]), Command::Repl);
+    }
+
+    #[test]
+    fn commands_are_read() {
+        assert_eq!(command(&["check", "a.yum"]), Command::Check(file("a.yum")));
+        assert_eq!(command(&["tokens", "a.yum"]), Command::Tokens(file("a.yum")));
+        assert_eq!(command(&["ast", "-"]), Command::Ast(Source::Stdin));
+        assert_eq!(command(&["--help"]), Command::Help);
+        assert_eq!(command(&["help"]), Command::Help);
+        assert_eq!(command(&["-V"]), Command::Version);
+    }
+
+    #[test]
+    fn remaining_arguments_are_given_to_the_program() {
+        let args = vec!["x".to_string(), "--verbose".to_string()];
+        assert_eq!(command(&["run", "a.yum", "x", "--verbose"]), Command::Run { source: file("a.yum"), args: args.clone() });
+        assert_eq!(command(&["a.yum", "x", "--verbose"]), Command::Run { source: file("a.yum"), args });
+        assert_eq!(command(&["eval", "-e", "print(\"hi\");"]), Command::Run { source: Source::Text("print(\"hi\");".to_string()), args: Vec::new() });
+    }
+
+    #[test]
+    fn options_come_before_the_command() {
+        let arguments = parse(&["-v", "--error-format=sarif", "--max-call-depth=50", "check", "a.yum"]).unwrap();
+        assert_eq!(arguments, Arguments { command: Command::Check(file("a.yum")), verbose: true, error_format: ErrorFormat::Sarif, max_call_depth: 50 });
+    }
+
+    #[test]
+    fn invalid_arguments_are_usage_errors() {
+        for args in [
+            &["check"][..],
+            &["eval"],
+            &["eval", "print(1);"],
+            &["check", "a.yum", "b.yum"],
+            &["--unknown"],
+            &["--error-format=xml", "a.yum"],
+            &["--max-call-depth=0", "a.yum"],
+            &["--max-call-depth=-1", "a.yum"],
+            &["--max-call-depth=lots", "a.yum"],
+        ] {
+            assert!(parse(args).is_err(), "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Shows errors with the lines of source code they occurred on,
+//! underlining the error's position with `^` and the positions of its
+//! other labels with `-`:
+//!
+//! ```text
+//! error[TypeError]: Cannot add int and string
+//!  --> test.yum:3:15
+//!   |
+//! 1 | let a = 1;
+//!   |     - int
+//! 2 | let b = "x";
+//!   |     - string
+//! 3 | print("{}", a + b);
+//!   |               ^
+//! ```
+
+use crate::errors::{self, Error};
+use crate::lexer::tokens::TokenPosition;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Most errors shown at once, as later errors are often caused by earlier ones
+pub const MAX_ERRORS: usize = 20;
+
+/// Underline beneath a line of source code
+struct Underline<'a> {
+    position: &'a TokenPosition,
+    message: Option<&'a str>,
+    primary: bool,
+}
+
+/// Describe an error with its source lines. Falls back to the one-line
+/// description when the source or the error's position is not known.
+pub fn render(error: &Error, colour: bool) -> String {
+    let file = match error.file {
+        Some(ref file) if !error.position.is_internal() => file,
+        _ => return error.to_string(),
+    };
+
+    let paint = |style: &str, text: &str| match colour {
+        true => format!("{}{}{}", style, text, RESET),
+        false => text.to_string(),
+    };
+
+    let lines: Vec<&str> = file.text.lines().collect();
+
+    let primary = error.labels.iter().find(|x| x.primary).map(|x| x.message.as_str());
+
+    let mut underlines = vec![Underline { position: &error.position, message: primary, primary: true }];
+    underlines.extend(error.labels.iter().filter(|x| !x.primary && !x.position.is_internal()).map(|x| Underline {
+        position: &x.position,
+        message: Some(&x.message),
+        primary: false,
+    }));
+
+    let mut line_numbers: Vec<u32> = underlines.iter().map(|x| x.position.line).collect();
+    line_numbers.sort();
+    line_numbers.dedup();
+
+    let width = line_numbers.last().map_or(1, |x| x.to_string().len());
+    let gutter = paint(BLUE, &format!("{} |", " ".repeat(width)));
+
+    let mut text = format!(
+        "{}{}\n{}{} {}:{}:{}",
+        paint(RED, "error"),
+        paint(BOLD, &format!("[{:?}]: {}", error.error_type, error.error_message)),
+        " ".repeat(width),
+        paint(BLUE, "-->"),
+        file.name,
+        error.position.line,
+        error.position.column,
+    );
+
+    text.push_str(&format!("\n{}", gutter));
+
+    let mut previous: Option<u32> = None;
+
+    for line_number in line_numbers {
+        let line = match lines.get(line_number as usize - 1) {
+            Some(line) => line,
+            // errors at the end of the file may be past its last line
+            None => "",
+        };
+
+        // skipped lines between those shown
+        if previous.is_some_and(|x| line_number > x + 1) {
+            text.push_str(&format!("\n{}", paint(BLUE, "...")));
+        }
+        previous = Some(line_number);
+
+        text.push_str(&format!(
+            "\n{} {}",
+            paint(BLUE, &format!("{:>width$} |", line_number, width = width)),
+            line
+        ));
+
+        // underlines on the same line are shown from left to right
+        let mut on_line: Vec<&Underline> = underlines.iter().filter(|x| x.position.line == line_number).collect();
+        on_line.sort_by_key(|x| x.position.column);
+
+        for underline in on_line {
+            let (padding, length) = underline_span(line, underline.position, &file.text);
+            let (style, mark) = match underline.primary {
+                true => (RED, "^"),
+                false => (BLUE, "-"),
+            };
+
+            let mut marks = mark.repeat(length);
+            if let Some(message) = underline.message {
+                marks = format!("{} {}", marks, message);
+            }
+
+            text.push_str(&format!("\n{} {}{}", gutter, padding, paint(style, &marks)));
+        }
+    }
+
+    for note in &error.notes {
+        text.push_str(&format!("\n{} {} {}", " ".repeat(width + 1), paint(BLUE, "="), paint(BOLD, &format!("note: {}", note))));
+    }
+
+    let calls = error.calls();
+    if !calls.is_empty() {
+        text.push_str(&format!("\n{}", paint(BOLD, "traceback (most recent call last):")));
+    }
+
+    // each call was made from within the function called before it
+    let mut caller = "<program>";
+
+    for (cycle, repeats) in calls {
+        for frame in &cycle {
+            let file = frame.file.as_ref().filter(|_| !frame.position.is_internal());
+
+            match file {
+                Some(file) => {
+                    let location = format!("{}:{}:{}", file.name, frame.position.line, frame.position.column);
+                    text.push_str(&format!("\n  {}, in {}", paint(BLUE, &location), caller));
+
+                    if let Some(line) = file.text.lines().nth(frame.position.line as usize - 1) {
+                        text.push_str(&format!("\n    {}", line.trim()));
+                    }
+                },
+                None => text.push_str(&format!("\n  {}, in {}", paint(BLUE, "<built-in>"), caller)),
+            }
+
+            caller = &frame.function;
+        }
+
+        if repeats > 0 {
+            text.push_str(&format!("\n  ... {}", errors::repeated(cycle.len(), repeats)));
+        }
+    }
+
+    text
+}
+
+/// Describe several errors in the order they occur in the source,
+/// showing at most `MAX_ERRORS` of them
+pub fn render_all(errors: &[Error], colour: bool) -> String {
+    let mut errors: Vec<&Error> = errors.iter().collect();
+    errors.sort_by_key(|x| (x.position.line, x.position.column));
+
+    let mut rendered: Vec<String> = errors.iter().take(MAX_ERRORS).map(|x| render(x, colour)).collect();
+
+    if errors.len() > MAX_ERRORS {
+        let hidden = errors.len() - MAX_ERRORS;
+        rendered.push(match hidden {
+            1 => "1 more error was not shown".to_string(),
+            _ => format!("{} more errors were not shown", hidden),
+        });
+    }
+
+    rendered.join("\n\n")
+}
+
+/// Whitespace before an underline, keeping tabs so it lines up with the
+/// source line, and the number of characters to underline
+fn underline_span(line: &str, position: &TokenPosition, source: &str) -> (String, usize) {
+    let before = position.column.saturating_sub(1) as usize;
+
+    let padding: String = line.chars()
+        .chain(std::iter::repeat(' '))
+        .take(before)
+        .map(|x| if x == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    // underline the whole span, up to the end of the line
+    let spanned = source.get(position.start as usize..position.end as usize).map_or(0, |x| x.chars().take_while(|x| *x != '\n').count());
+    let remaining = line.chars().count().saturating_sub(before);
+
+    (padding, spanned.min(remaining).max(1))
+}
\ No newline at end of file
@@ -0,0 +1,381 @@
+//! Writes errors in formats other programs can read, such as editors and
+//! CI systems which annotate the lines errors occurred on.
+//!
+//! `json` writes one object with a `diagnostics` array, and `sarif` writes
+//! a SARIF 2.1.0 log. Both are described in the README. Unlike the human
+//! format, they include every error rather than the first `MAX_ERRORS`.
+
+use std::fmt::Display;
+
+use crate::diagnostics;
+use crate::errors::{Error, SourceFile};
+use crate::lexer::tokens::TokenPosition;
+
+/// Version of the `json` format, increased when a field is removed or
+/// changes meaning. Fields may be added without changing it.
+pub const JSON_VERSION: i64 = 1;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Format errors are written in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    /// Source lines with the error underlined, for people to read
+    Human,
+    Json,
+    Sarif,
+}
+
+impl ErrorFormat {
+    pub fn from(name: &str) -> Option<ErrorFormat> {
+        match name {
+            "human" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            "sarif" => Some(ErrorFormat::Sarif),
+            _ => None,
+        }
+    }
+}
+
+/// Describe errors in the order they occur in the source.
+/// Human descriptions are coloured for a terminal if `colour` is true.
+pub fn render(errors: &[Error], format: ErrorFormat, colour: bool) -> String {
+    match format {
+        ErrorFormat::Human => diagnostics::render_all(errors, colour),
+        ErrorFormat::Json => json(errors),
+        ErrorFormat::Sarif => sarif(errors),
+    }
+}
+
+/// Describe errors as a JSON object
+pub fn json(errors: &[Error]) -> String {
+    let diagnostics = in_source_order(errors).into_iter().map(|error| {
+        let file = error.file.as_deref();
+
+        let labels = error.labels.iter().filter(|x| !x.position.is_internal()).map(|label| {
+            let (start, end) = locations(&label.position, file);
+            Json::Object(vec![
+                ("message", Json::from(&label.message)),
+                ("primary", Json::Bool(label.primary)),
+                ("start", start),
+                ("end", end),
+            ])
+        });
+
+        let (start, end) = locations(&error.position, file);
+
+        Json::Object(vec![
+            ("type", Json::from(&format!("{:?}", error.error_type))),
+            ("severity", Json::from("error")),
+            ("message", Json::from(&error.error_message)),
+            ("file", file.map_or(Json::Null, |x| Json::from(&x.name))),
+            ("start", start),
+            ("end", end),
+            ("labels", Json::Array(labels.collect())),
+            ("notes", Json::Array(error.notes.iter().map(Json::from).collect())),
+            ("traceback", Json::Array(error.frames().iter().rev().map(|frame| {
+                let (start, end) = locations(&frame.position, frame.file.as_deref());
+                Json::Object(vec![
+                    ("function", Json::from(&frame.function)),
+                    ("file", frame.file.as_ref().map_or(Json::Null, |x| Json::from(&x.name))),
+                    ("start", start),
+                    ("end", end),
+                ])
+            }).collect())),
+        ])
+    });
+
+    Json::Object(vec![
+        ("version", Json::Number(JSON_VERSION)),
+        ("diagnostics", Json::Array(diagnostics.collect())),
+    ]).to_string()
+}
+
+/// Describe errors as a SARIF log, with one rule for each type of error
+pub fn sarif(errors: &[Error]) -> String {
+    let errors = in_source_order(errors);
+
+    let mut rules: Vec<String> = errors.iter().map(|x| format!("{:?}", x.error_type)).collect();
+    rules.sort();
+    rules.dedup();
+
+    let results = errors.iter().map(|error| {
+        let file = error.file.as_deref();
+        let primary = error.labels.iter().find(|x| x.primary);
+
+        let mut location = sarif_location(&error.position, file);
+        if let (Json::Object(ref mut fields), Some(label)) = (&mut location, primary) {
+            fields.push(("message", sarif_message(&label.message)));
+        }
+
+        let related = error.labels.iter().filter(|x| !x.primary && !x.position.is_internal()).enumerate().map(|(id, label)| {
+            match sarif_location(&label.position, file) {
+                Json::Object(mut fields) => {
+                    fields.insert(0, ("id", Json::Number(id as i64)));
+                    fields.push(("message", sarif_message(&label.message)));
+                    Json::Object(fields)
+                },
+                other => other,
+            }
+        });
+
+        // errors in built-in code have no location
+        let locations = match error.position.is_internal() {
+            true => Vec::new(),
+            false => vec![location],
+        };
+
+        // the calls the error passed out of, the most recent first
+        let frames = error.frames().iter().filter(|x| !x.position.is_internal()).map(|frame| {
+            Json::Object(vec![("location", match sarif_location(&frame.position, frame.file.as_deref()) {
+                Json::Object(mut fields) => {
+                    fields.push(("message", sarif_message(&format!("call to {}", frame.function))));
+                    Json::Object(fields)
+                },
+                other => other,
+            })])
+        });
+
+        let stacks = match error.frames().is_empty() {
+            true => Vec::new(),
+            false => vec![Json::Object(vec![
+                ("message", sarif_message("traceback")),
+                ("frames", Json::Array(frames.collect())),
+            ])],
+        };
+
+        Json::Object(vec![
+            ("ruleId", Json::from(&format!("{:?}", error.error_type))),
+            ("level", Json::from("error")),
+            ("message", sarif_message(&error.error_message)),
+            ("locations", Json::Array(locations)),
+            ("relatedLocations", Json::Array(related.collect())),
+            ("stacks", Json::Array(stacks)),
+            ("properties", Json::Object(vec![
+                ("notes", Json::Array(error.notes.iter().map(Json::from).collect())),
+            ])),
+        ])
+    });
+
+    let driver = Json::Object(vec![
+        ("name", Json::from("yum_compiler")),
+        ("version", Json::from(env!("CARGO_PKG_VERSION"))),
+        ("rules", Json::Array(rules.iter().map(|x| Json::Object(vec![("id", Json::from(x))])).collect())),
+    ]);
+
+    let run = Json::Object(vec![
+        ("tool", Json::Object(vec![("driver", driver)])),
+        // columns count characters, rather than UTF-16 code units
+        ("columnKind", Json::from("unicodeCodePoints")),
+        ("results", Json::Array(results.collect())),
+    ]);
+
+    Json::Object(vec![
+        ("$schema", Json::from(SARIF_SCHEMA)),
+        ("version", Json::from("2.1.0")),
+        ("runs", Json::Array(vec![run])),
+    ]).to_string()
+}
+
+fn in_source_order(errors: &[Error]) -> Vec<&Error> {
+    let mut errors: Vec<&Error> = errors.iter().collect();
+    errors.sort_by_key(|x| (x.position.line, x.position.column));
+    errors
+}
+
+fn sarif_message(text: &str) -> Json {
+    Json::Object(vec![("text", Json::from(text))])
+}
+
+fn sarif_location(position: &TokenPosition, file: Option<&SourceFile>) -> Json {
+    let (start, end) = span(position, file);
+
+    let mut physical = Vec::new();
+    if let Some(file) = file {
+        physical.push(("artifactLocation", Json::Object(vec![("uri", Json::from(&file.name))])));
+    }
+    physical.push(("region", Json::Object(vec![
+        ("startLine", Json::Number(start.line as i64)),
+        ("startColumn", Json::Number(start.column as i64)),
+        ("endLine", Json::Number(end.line as i64)),
+        ("endColumn", Json::Number(end.column as i64)),
+    ])));
+
+    Json::Object(vec![("physicalLocation", Json::Object(physical))])
+}
+
+/// Start and end of a position as JSON objects, which are null if it is not in the source
+fn locations(position: &TokenPosition, file: Option<&SourceFile>) -> (Json, Json) {
+    if position.is_internal() {
+        return (Json::Null, Json::Null);
+    }
+
+    let (start, end) = span(position, file);
+    (start.to_json(), end.to_json())
+}
+
+/// Line, column and byte offset within the source
+struct Location {
+    line: u32,
+    column: u32,
+    offset: usize,
+}
+
+impl Location {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("line", Json::Number(self.line as i64)),
+            ("column", Json::Number(self.column as i64)),
+            ("offset", Json::Number(self.offset as i64)),
+        ])
+    }
+}
+
+/// Start of a position, and the location just past its last character.
+/// Without the source, the end is assumed to be on the same line.
+fn span(position: &TokenPosition, file: Option<&SourceFile>) -> (Location, Location) {
+    let start = Location { line: position.line, column: position.column, offset: position.start as usize };
+    let end_offset = position.end.max(position.start) as usize;
+
+    let spanned = file.and_then(|x| x.text.get(start.offset..end_offset));
+
+    let end = match spanned {
+        Some(text) => match text.rfind('\n') {
+            Some(newline) => Location {
+                line: position.line + text.matches('\n').count() as u32,
+                column: text[newline + 1..].chars().count() as u32 + 1,
+                offset: end_offset,
+            },
+            None => Location { column: position.column + text.chars().count() as u32, offset: end_offset, ..start },
+        },
+        None => Location { column: position.column + (position.end.max(position.start) - position.start), offset: end_offset, ..start },
+    };
+
+    (start, end)
+}
+
+/// JSON value, written without whitespace
+enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    /// Fields in the order they are written
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl<S: AsRef<str> + ?Sized> From<&S> for Json {
+    fn from(text: &S) -> Json {
+        Json::String(text.as_ref().to_string())
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(text) => write_string(f, text),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+/// Write a string in quotes, escaping the characters JSON requires
+fn write_string(f: &mut std::fmt::Formatter<'_>, text: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorType;
+    use crate::lexer::Lexer;
+
+    /// First error found while lexing the text, in a file named `test.yum`
+    fn lexer_error(text: &str) -> Error {
+        let file = SourceFile::new("test.yum", text);
+        Lexer::new(text.to_string()).make_tokens().unwrap_err().with_file(&file)
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let text = Json::from("quote \" backslash \\ newline \n tab \t bell \u{7} é").to_string();
+        assert_eq!(text, r#""quote \" backslash \\ newline \n tab \t bell \u0007 é""#);
+    }
+
+    #[test]
+    fn json_has_the_documented_fields() {
+        let error = lexer_error("let a = 1 $ 2;")
+            .with_note("a \"note\"".to_string());
+
+        assert_eq!(json(&[error]), concat!(
+            r#"{"version":1,"diagnostics":[{"type":"InvalidToken","severity":"error","message":"Unexpected character: '$'","file":"test.yum","#,
+            r#""start":{"line":1,"column":11,"offset":10},"end":{"line":1,"column":12,"offset":11},"#,
+            r#""labels":[],"notes":["a \"note\""],"traceback":[]}]}"#,
+        ));
+    }
+
+    #[test]
+    fn json_positions_of_built_in_code_are_null() {
+        let error = Error::new_runtime(ErrorType::ArgumentError, "Bad \"value\"".to_string(), &TokenPosition::internal());
+        let text = json(&[error]);
+
+        assert!(text.contains(r#""message":"Bad \"value\"","file":null,"start":null,"end":null"#), "{}", text);
+    }
+
+    #[test]
+    fn sarif_has_the_documented_fields() {
+        let error = lexer_error("let a = \"abc;");
+
+        assert_eq!(sarif(&[error]), format!(concat!(
+            r#"{{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","runs":[{{"#,
+            r#""tool":{{"driver":{{"name":"yum_compiler","version":"{}","rules":[{{"id":"InvalidToken"}}]}}}},"#,
+            r#""columnKind":"unicodeCodePoints","results":[{{"ruleId":"InvalidToken","level":"error","message":{{"text":"Unterminated string"}},"#,
+            r#""locations":[{{"physicalLocation":{{"artifactLocation":{{"uri":"test.yum"}},"region":{{"startLine":1,"startColumn":9,"endLine":1,"endColumn":10}}}}}}],"#,
+            r#""relatedLocations":[],"stacks":[],"properties":{{"notes":[]}}}}]}}]}}"#,
+        ), env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn lexer_errors_have_a_width() {
+        for text in ["a $ b", "a | b", "let a = \"abc", "x = 0b102;", "x = 99999999999;"] {
+            let error = lexer_error(text);
+            let (start, end) = span(&error.position, error.file.as_deref());
+            assert!(end.column > start.column, "{}: {:?}", text, error);
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use std::fmt::Display;
+use std::rc::Rc;
 
+use crate::interpreter::symbols::{Symbol, SymbolType};
 use crate::lexer::LexerPosition;
 use crate::lexer::tokens::TokenPosition;
+use crate::diagnostics;
+use crate::suggestions;
 
 /// Represents an error
 #[derive(Debug)]
@@ -9,6 +13,59 @@ pub struct Error {
     pub error_type: ErrorType,
     pub error_message: String,
     pub position: TokenPosition,
+    /// File the error occurred in, if known
+    pub file: Option<Rc<SourceFile>>,
+    /// Descriptions of the error's position and of other positions which explain it
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    /// Calls which were being made when the error occurred. Boxed to keep
+    /// errors small, as most do not occur inside a function.
+    pub traceback: Option<Box<Traceback>>,
+    /// Value carried out of a function by an early return,
+    /// or the code given to `exit`
+    pub return_value: Option<Box<Symbol>>,
+}
+
+/// Source code of a file, shared by the errors found in it
+/// so that they can show the lines they occurred on
+#[derive(Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub text: String,
+}
+
+/// Position referred to by an error
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub position: TokenPosition,
+    pub message: String,
+    /// Whether the label describes where the error occurred
+    pub primary: bool,
+}
+
+/// Calls an error passed out of, the most recent first
+#[derive(Debug, Clone, Default)]
+pub struct Traceback {
+    pub frames: Vec<Frame>,
+}
+
+/// Call of a function, recorded as an error passes out of it
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Name of the function called
+    pub function: String,
+    /// Where the function was called from
+    pub position: TokenPosition,
+    /// File the function was called from, if known
+    pub file: Option<Rc<SourceFile>>,
+}
+
+impl Frame {
+    /// Whether both are calls of the same function from the same place
+    fn is_same_call(&self, other: &Frame) -> bool {
+        let same_file = self.file.as_ref().map(|x| &x.name) == other.file.as_ref().map(|x| &x.name);
+        self.function == other.function && self.position == other.position && same_file
+    }
 }
 
 #[derive(Debug)]
@@ -24,35 +81,248 @@ pub enum ErrorType {
     UndefinedVariable,
     ArgumentError,
     IOError,
+    ImportError,
+    ConstantError,
+    RecursionError,
+    EarlyReturn,
+    Exit,
 }
 
 impl Error {
-    /// New lexer error
-    pub fn new_lexer(error_type: ErrorType, error_message: String, position: &LexerPosition) -> Error {
+    fn new(error_type: ErrorType, error_message: String, position: TokenPosition) -> Error {
         Error {
             error_type,
             error_message,
-            position: TokenPosition::from(position),
+            position,
+            file: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            traceback: None,
+            return_value: None,
         }
     }
 
+    /// New lexer error at the character which caused it
+    pub fn new_lexer(error_type: ErrorType, error_message: String, position: &LexerPosition) -> Error {
+        Error::new(error_type, error_message, TokenPosition::character(position))
+    }
+
+    /// New lexer error spanning from one position up to another
+    pub fn new_lexer_between(error_type: ErrorType, error_message: String, start: &LexerPosition, end: &LexerPosition) -> Error {
+        Error::new(error_type, error_message, TokenPosition::between(start, end))
+    }
+
     /// New parser error
     pub fn new_parser(error_message: String, position: &TokenPosition) -> Error {
-        Error {
-            error_type: ErrorType::ParserError,
-            error_message,
-            position: position.clone(),
-        }
+        Error::new(ErrorType::ParserError, error_message, *position)
     }
 
     /// New runtime error
     pub fn new_runtime(error_type: ErrorType, error_message: String, position: &TokenPosition) -> Error {
+        Error::new(error_type, error_message, *position)
+    }
+
+    /// New early return, used by `?` to leave the enclosing function.
+    /// The function call catches it and returns the carried value.
+    pub fn new_early_return(value: Symbol, position: &TokenPosition) -> Error {
         Error {
-            error_type,
-            error_message,
-            position: position.clone(),
+            return_value: Some(Box::new(value)),
+            ..Error::new(ErrorType::EarlyReturn, "Cannot return early outside of a function".to_string(), *position)
+        }
+    }
+
+    /// New exit, used by `exit` to stop the program with an exit code.
+    /// Nothing catches it, so it leaves every function and loop.
+    pub fn new_exit(code: Symbol, position: &TokenPosition) -> Error {
+        Error {
+            error_message: format!("Program exited with code {}", code),
+            return_value: Some(Box::new(code)),
+            ..Error::new(ErrorType::Exit, String::new(), *position)
+        }
+    }
+
+    /// Set the file the error occurred in, unless it is already known.
+    /// Calls recorded since the error last passed into a file were made from it too.
+    pub fn with_file(mut self, file: &Rc<SourceFile>) -> Error {
+        if self.file.is_none() {
+            self.file = Some(file.clone());
+        }
+
+        if let Some(ref mut traceback) = self.traceback {
+            for frame in traceback.frames.iter_mut().filter(|x| x.file.is_none()) {
+                frame.file = Some(file.clone());
+            }
+        }
+
+        self
+    }
+
+    /// Record a call the error passed out of. Errors in built-in functions
+    /// have no position of their own, so are moved to where the function was
+    /// called instead.
+    pub fn with_call(mut self, function: &str, position: &TokenPosition) -> Error {
+        if self.is_early_return() || self.is_exit() {
+            return self;
+        }
+
+        if self.position.is_internal() {
+            self.position = *position;
+        } else {
+            let traceback = self.traceback.get_or_insert_with(Box::default);
+            traceback.frames.push(Frame { function: function.to_string(), position: *position, file: None });
+        }
+
+        self
+    }
+
+    /// Describe what is wrong at the error's position
+    pub fn labelled(mut self, message: String) -> Error {
+        self.labels.push(Label { position: self.position, message, primary: true });
+        self
+    }
+
+    /// Point out another position which explains the error
+    pub fn with_label(mut self, position: &TokenPosition, message: String) -> Error {
+        self.labels.push(Label { position: *position, message, primary: false });
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Error {
+        self.notes.push(note);
+        self
+    }
+
+    /// Suggest the candidates closest to a misspelt name, if any are close to it
+    pub fn with_suggestions<'a>(self, name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Error {
+        match suggestions::did_you_mean(name, candidates) {
+            Some(note) => self.with_note(note),
+            None => self,
+        }
+    }
+
+    /// Describe the error with the lines of source code it occurred on,
+    /// coloured for a terminal if `colour` is true
+    pub fn render(&self, colour: bool) -> String {
+        diagnostics::render(self, colour)
+    }
+
+    /// Calls which were being made when the error occurred, the most recent first
+    pub fn frames(&self) -> &[Frame] {
+        self.traceback.as_ref().map_or(&[], |x| &x.frames)
+    }
+
+    /// Calls which were being made when the error occurred, the most recent
+    /// last, grouped into cycles of calls with how many more times each cycle
+    /// was made again straight after itself. Recursion is then one call which
+    /// is repeated, and mutual recursion a cycle of several calls.
+    pub fn calls(&self) -> Vec<(Vec<&Frame>, usize)> {
+        let frames: Vec<&Frame> = self.frames().iter().rev().collect();
+        let mut calls = Vec::new();
+        let mut start = 0;
+
+        while start < frames.len() {
+            let (length, repeats) = longest_cycle(&frames[start..]);
+            calls.push((frames[start..start + length].to_vec(), repeats));
+            start += length * (repeats + 1);
+        }
+
+        calls
+    }
+
+    /// Whether the error is an early return rather than a real error
+    pub fn is_early_return(&self) -> bool {
+        matches!(self.error_type, ErrorType::EarlyReturn)
+    }
+
+    /// Whether the error is a call to `exit` rather than a real error
+    pub fn is_exit(&self) -> bool {
+        matches!(self.error_type, ErrorType::Exit)
+    }
+
+    /// Code the process exits with because of this error
+    pub fn exit_code(&self) -> i32 {
+        match self.return_value.as_deref() {
+            Some(Symbol { value: SymbolType::Integer(code), .. }) if self.is_exit() => *code,
+            _ => self.error_type.exit_code(),
+        }
+    }
+}
+
+impl ErrorType {
+    /// Code the process exits with after an error of this type.
+    /// Errors are grouped by when they are found, so scripts can
+    /// tell a program which did not parse from one which failed.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorType::SyntaxError | ErrorType::InvalidToken | ErrorType::ParserError | ErrorType::InvalidSyntax => exit_codes::SYNTAX,
+            ErrorType::AssertError => exit_codes::ASSERTION,
+            ErrorType::Exit => exit_codes::SUCCESS,
+            _ => exit_codes::RUNTIME,
+        }
+    }
+}
+
+impl SourceFile {
+    pub fn new(name: &str, text: &str) -> Rc<SourceFile> {
+        Rc::new(SourceFile { name: name.to_string(), text: text.to_string() })
+    }
+}
+
+/// Most calls in a cycle which is collapsed in tracebacks. Looking for
+/// longer cycles would make deep tracebacks slow to describe.
+const MAX_CYCLE_LENGTH: usize = 16;
+
+/// Length of the cycle of calls at the start of the frames which repeats
+/// over the most of them, and how many more times it repeats. A single call
+/// which does not repeat is a cycle of one.
+fn longest_cycle(frames: &[&Frame]) -> (usize, usize) {
+    let mut best = (1, 0);
+
+    for length in 1..=(frames.len() / 2).min(MAX_CYCLE_LENGTH) {
+        let cycle = &frames[..length];
+        let repeats = frames[length..].chunks_exact(length)
+            .take_while(|chunk| chunk.iter().zip(cycle).all(|(a, b)| a.is_same_call(b)))
+            .count();
+
+        // shorter cycles are preferred when they cover as many calls
+        if repeats > 0 && length * (repeats + 1) > best.0 * (best.1 + 1) {
+            best = (length, repeats);
         }
     }
+
+    best
+}
+
+/// Number of things for an error message, such as "1 argument" or "2 arguments"
+pub fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
+
+/// Describe how many more times a cycle of calls was made, such as
+/// "repeated 4 more times" or "last 2 calls repeated 4 more times"
+pub fn repeated(length: usize, repeats: usize) -> String {
+    match length {
+        1 => format!("repeated {} more times", repeats),
+        _ => format!("last {} repeated {} more times", count(length, "call"), repeats),
+    }
+}
+
+/// Codes the process exits with
+pub mod exit_codes {
+    pub const SUCCESS: i32 = 0;
+    /// Error while running, or a name or type error found while checking
+    pub const RUNTIME: i32 = 1;
+    /// Invalid command-line arguments
+    pub const USAGE: i32 = 2;
+    /// Error while lexing or parsing
+    pub const SYNTAX: i32 = 3;
+    /// Failed assertion
+    pub const ASSERTION: i32 = 4;
+    /// Source code which could not be read
+    pub const NO_INPUT: i32 = 5;
 }
 
 impl Display for Error {
@@ -70,7 +340,34 @@ impl Display for Error {
                 self.error_message, 
                 self.position.line, 
                 self.position.column,
-            )
+            )?;
+
+            if let Some(ref file) = self.file {
+                write!(f, " in {}", file.name)?;
+            }
+
+            for label in &self.labels {
+                match label.primary {
+                    true => write!(f, "\n    {}", label.message)?,
+                    false => write!(f, "\n    {} at line {}, column {}", label.message, label.position.line, label.position.column)?,
+                }
+            }
+
+            for note in &self.notes {
+                write!(f, "\n    note: {}", note)?;
+            }
+
+            for (cycle, repeats) in self.calls() {
+                for frame in &cycle {
+                    write!(f, "\n    called {} at line {}, column {}", frame.function, frame.position.line, frame.position.column)?;
+                }
+
+                if repeats > 0 {
+                    write!(f, "\n    ... {}", repeated(cycle.len(), repeats))?;
+                }
+            }
+
+            Ok(())
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,18 @@
+use crate::interpreter::symbol_table::SymbolTable;
+use crate::interpreter::symbols::{Symbol, SymbolType};
+use crate::errors::{Error, ErrorType};
+use crate::lexer::tokens::TokenPosition;
+
+/// Stop the program, exiting the process with a code from 0 to 255.
+/// Example: exit(1)
+pub fn exit_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    match symbol_args[0].value {
+        // the process can only exit with the lowest byte of the code
+        SymbolType::Integer(code) if !(0..=255).contains(&code) => Err(Error::new_runtime(
+            ErrorType::ArgumentError,
+            format!("Exit code must be between 0 and 255, got {}", code),
+            &TokenPosition::internal(),
+        )),
+        _ => Err(Error::new_exit(symbol_args[0].clone(), &TokenPosition::internal())),
+    }
+}
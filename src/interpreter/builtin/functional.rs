@@ -0,0 +1,235 @@
+use std::cmp::Ordering;
+
+use crate::interpreter::symbol_table::SymbolTable;
+use crate::interpreter::iterators::{collect, next, SharedIterator};
+use crate::interpreter::symbols::{FunctionSymbol, Symbol, SymbolType};
+use crate::errors::{Error, ErrorType};
+use crate::lexer::tokens::TokenPosition;
+
+/// Apply a function to every element of an iterable, giving a list.
+/// Example: map([1, 2, 3], double)
+pub fn map_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let elements = iterator(&symbol_args[0]);
+    let func = function(&symbol_args[1])?;
+    let mut mapped: Vec<Symbol> = Vec::new();
+
+    while let Some(element) = next(&elements, symbol_table)? {
+        mapped.push(func.call(vec![element], symbol_table, &TokenPosition::internal())?);
+    }
+
+    Ok(list(mapped))
+}
+
+/// Keep the elements of an iterable for which a function returns a true value.
+/// Example: filter([1, 2, 3, 4], is_even)
+pub fn filter_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let elements = iterator(&symbol_args[0]);
+    let func = function(&symbol_args[1])?;
+    let mut kept: Vec<Symbol> = Vec::new();
+
+    while let Some(element) = next(&elements, symbol_table)? {
+        let keep = func.call(vec![element.clone()], symbol_table, &TokenPosition::internal())?;
+
+        if is_true("filter", &keep)? {
+            kept.push(element);
+        }
+    }
+
+    Ok(list(kept))
+}
+
+/// Combine the elements of an iterable into one value, from left to right.
+/// Starts from the first element when no initial value is given.
+/// Example: reduce([1, 2, 3], add, 0)
+pub fn reduce_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let elements = iterator(&symbol_args[0]);
+    let func = function(&symbol_args[1])?;
+
+    let mut accumulated = match symbol_args[2].value {
+        SymbolType::None => match next(&elements, symbol_table)? {
+            Some(first) => first,
+            None => return Err(Error::new_runtime(
+                ErrorType::ArgumentError,
+                "Cannot reduce an empty iterable without an initial value".to_string(),
+                &TokenPosition::internal()
+            )),
+        },
+        _ => symbol_args[2].clone(),
+    };
+
+    while let Some(element) = next(&elements, symbol_table)? {
+        accumulated = func.call(vec![accumulated, element], symbol_table, &TokenPosition::internal())?;
+    }
+
+    Ok(accumulated)
+}
+
+/// Whether any element of an iterable is true, stopping at the first.
+/// Example: any([0, 1, 0])
+pub fn any_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let elements = iterator(&symbol_args[0]);
+
+    while let Some(element) = next(&elements, symbol_table)? {
+        if is_true("any", &element)? {
+            return Ok(integer(1));
+        }
+    }
+
+    Ok(integer(0))
+}
+
+/// Whether every element of an iterable is true, stopping at the first false.
+/// Example: all([1, 1, 0])
+pub fn all_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let elements = iterator(&symbol_args[0]);
+
+    while let Some(element) = next(&elements, symbol_table)? {
+        if !is_true("all", &element)? {
+            return Ok(integer(0));
+        }
+    }
+
+    Ok(integer(1))
+}
+
+/// Pair up the elements of iterables into tuples, stopping at the shortest.
+/// Example: zip([1, 2], ["a", "b"])
+pub fn zip_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let iterators: Vec<SharedIterator> = symbol_args.iter().map(iterator).collect();
+    let mut zipped: Vec<Symbol> = Vec::new();
+
+    if iterators.is_empty() {
+        return Ok(list(zipped));
+    }
+
+    loop {
+        let mut values: Vec<Symbol> = Vec::new();
+
+        for iterator in &iterators {
+            match next(iterator, symbol_table)? {
+                Some(value) => values.push(value),
+                None => return Ok(list(zipped)),
+            }
+        }
+
+        zipped.push(tuple(values));
+    }
+}
+
+/// Pair each element of an iterable with its index.
+/// Example: enumerate(["a", "b"])
+pub fn enumerate_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let enumerated = collect(&iterator(&symbol_args[0]), symbol_table)?.into_iter()
+        .enumerate()
+        .map(|(index, element)| tuple(vec![integer(index as i32), element]))
+        .collect();
+
+    Ok(list(enumerated))
+}
+
+/// Sort an iterable of numbers, strings, or lists and tuples of them,
+/// optionally comparing the result of a key function for each element.
+/// Example: sorted(names, length)
+pub fn sorted_func(symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let elements = collect(&iterator(&symbol_args[0]), symbol_table)?;
+
+    let keys = match symbol_args[1].value {
+        SymbolType::None => elements.clone(),
+        _ => {
+            let func = function(&symbol_args[1])?;
+            let mut keys: Vec<Symbol> = Vec::new();
+
+            for element in &elements {
+                keys.push(func.call(vec![element.clone()], symbol_table, &TokenPosition::internal())?);
+            }
+
+            keys
+        },
+    };
+
+    // sort indices by key, remembering the first pair which cannot be compared
+    let mut indices: Vec<usize> = (0..elements.len()).collect();
+    let mut error: Option<Error> = None;
+
+    indices.sort_by(|a, b| match compare(&keys[*a], &keys[*b]) {
+        Ok(ordering) => ordering,
+        Err(e) => {
+            error.get_or_insert(e);
+            Ordering::Equal
+        },
+    });
+
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    Ok(list(indices.into_iter().map(|x| elements[x].clone()).collect()))
+}
+
+/// Order two values, failing if they cannot be compared
+fn compare(a: &Symbol, b: &Symbol) -> Result<Ordering, Error> {
+    let ordering = match (&a.value, &b.value) {
+        (SymbolType::Integer(a), SymbolType::Integer(b)) => Some(a.cmp(b)),
+        (SymbolType::Integer(a), SymbolType::Float(b)) => (*a as f32).partial_cmp(b),
+        (SymbolType::Float(a), SymbolType::Integer(b)) => a.partial_cmp(&(*b as f32)),
+        (SymbolType::Float(a), SymbolType::Float(b)) => a.partial_cmp(b),
+        (SymbolType::String(a), SymbolType::String(b)) => Some(a.cmp(b)),
+        (SymbolType::List(a), SymbolType::List(b)) | (SymbolType::Tuple(a), SymbolType::Tuple(b)) => {
+            for (a, b) in a.iter().zip(b) {
+                match compare(a, b)? {
+                    Ordering::Equal => continue,
+                    ordering => return Ok(ordering),
+                }
+            }
+
+            Some(a.len().cmp(&b.len()))
+        },
+        _ => None,
+    };
+
+    ordering.ok_or_else(|| Error::new_runtime(
+        ErrorType::TypeError,
+        format!("Cannot compare {} and {}", a.value.type_name(), b.value.type_name()),
+        &TokenPosition::internal()
+    ))
+}
+
+/// Whether a value returned to a built-in is true
+fn is_true(name: &str, symbol: &Symbol) -> Result<bool, Error> {
+    match symbol.value {
+        SymbolType::Integer(value) => Ok(value != 0),
+        _ => Err(Error::new_runtime(
+            ErrorType::TypeError,
+            format!("{} expected an int condition, found {}", name, symbol.value.type_name()),
+            &TokenPosition::internal()
+        )),
+    }
+}
+
+fn function(symbol: &Symbol) -> Result<&FunctionSymbol, Error> {
+    match symbol.value {
+        SymbolType::Function(ref func) => Ok(func),
+        _ => Err(Error::new_runtime(
+            ErrorType::TypeError,
+            format!("Expected a function, found {}", symbol.value.type_name()),
+            &TokenPosition::internal()
+        )),
+    }
+}
+
+/// Iterator over an iterable argument, which the signature has already checked
+fn iterator(symbol: &Symbol) -> SharedIterator {
+    symbol.iterate().expect("Argument must be iterable")
+}
+
+fn list(elements: Vec<Symbol>) -> Symbol {
+    Symbol::new(SymbolType::List(elements), TokenPosition::internal())
+}
+
+fn tuple(elements: Vec<Symbol>) -> Symbol {
+    Symbol::new(SymbolType::Tuple(elements), TokenPosition::internal())
+}
+
+fn integer(value: i32) -> Symbol {
+    Symbol::new(SymbolType::Integer(value), TokenPosition::internal())
+}
\ No newline at end of file
@@ -2,12 +2,9 @@ use crate::interpreter::symbol_table::SymbolTable;
 use crate::interpreter::symbols::{Symbol, SymbolType};
 use crate::errors::{Error, ErrorType};
 use crate::lexer::tokens::TokenPosition;
-use crate::parser::nodes::Node;
 use std::io::{stdin,stdout,Write};
 
-pub fn input_func(symbol_table: &mut SymbolTable, args: &Vec<Node>) -> Result<Symbol, Error> {
-    let symbol_args = super::evaluate_args(symbol_table, args)?;
-
+pub fn input_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
     let prefix = symbol_args[0].value.to_string();
     print!("{}", prefix);
     
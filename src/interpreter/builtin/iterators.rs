@@ -0,0 +1,59 @@
+use crate::interpreter::symbol_table::SymbolTable;
+use crate::interpreter::symbols::{Symbol, SymbolType};
+use crate::interpreter::iterators::IteratorState;
+use crate::errors::{Error, ErrorType};
+use crate::lexer::tokens::TokenPosition;
+
+/// Lazily count from a start up to, but not including, a stop.
+/// Given one argument, counts from 0 up to it.
+/// Example: range(5), range(1, 10, 2)
+pub fn range_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let (start, stop) = match (&symbol_args[0].value, &symbol_args[1].value) {
+        (SymbolType::Integer(stop), SymbolType::None) => (0, *stop),
+        (SymbolType::Integer(start), SymbolType::Integer(stop)) => (*start, *stop),
+        (_, stop) => return Err(Error::new_runtime(
+            ErrorType::TypeError,
+            format!("Expected argument 'stop' of type int, found {}", stop.type_name()),
+            &TokenPosition::internal()
+        )),
+    };
+
+    let step = match symbol_args[2].value {
+        SymbolType::Integer(0) => return Err(Error::new_runtime(
+            ErrorType::ArgumentError,
+            "Range step cannot be 0".to_string(),
+            &TokenPosition::internal()
+        )),
+        SymbolType::Integer(step) => step,
+        _ => 1,
+    };
+
+    Ok(iterator(IteratorState::Range { next: start, stop, step }))
+}
+
+/// Lazily produce at most a number of values of an iterable.
+/// Example: take(numbers(), 3)
+pub fn take_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let remaining = match symbol_args[1].value {
+        SymbolType::Integer(count) if count >= 0 => count as usize,
+        _ => return Err(Error::new_runtime(
+            ErrorType::ArgumentError,
+            format!("Cannot take {} values", symbol_args[1]),
+            &TokenPosition::internal()
+        )),
+    };
+
+    let inner = symbol_args[0].iterate().expect("Argument must be iterable");
+    Ok(iterator(IteratorState::Take { inner, remaining }))
+}
+
+/// Lazily produce every value of each iterable in turn.
+/// Example: chain([1, 2], range(3, 5))
+pub fn chain_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let iterators = symbol_args.iter().map(|x| x.iterate().expect("Argument must be iterable")).collect();
+    Ok(iterator(IteratorState::Chain { iterators, index: 0 }))
+}
+
+fn iterator(state: IteratorState) -> Symbol {
+    Symbol::new(SymbolType::Iterator(state.shared()), TokenPosition::internal())
+}
\ No newline at end of file
@@ -1,18 +1,45 @@
-use crate::parser::nodes::Node;
-use super::{symbols::Symbol, symbol_table::SymbolTable};
-use crate::errors::Error;
-use crate::parser::nodes::NodeVisit;
+use super::symbols::{Parameter, ParameterType, Symbol, SymbolType};
+use crate::lexer::tokens::TokenPosition;
 
 pub mod print;
 pub mod input;
+pub mod exit;
+pub mod result;
+pub mod functional;
+pub mod iterators;
+pub mod numbers;
 
-fn evaluate_args(symbol_table: &mut SymbolTable, args: &Vec<Node>) -> Result<Vec<Symbol>, Error> {
-    let mut symbol_args = Vec::new();
+/// Describes the parameters of a built-in function.
+/// Built-ins receive their fixed and optional arguments in order,
+/// followed by every variadic argument.
+///
+/// Example: `Signature::new().required("text", ParameterType::String).variadic("arguments", ParameterType::Any)`
+#[derive(Debug, Clone, Default)]
+pub struct Signature {
+    pub parameters: Vec<Parameter>,
+}
 
-    for arg in args {
-        let symbol = arg.visit(symbol_table)?;
-        symbol_args.push(symbol);
+impl Signature {
+    pub fn new() -> Signature {
+        Signature::default()
     }
 
-    Ok(symbol_args)
+    /// Parameter which must always be given
+    pub fn required(mut self, name: &str, expected: ParameterType) -> Signature {
+        self.parameters.push(Parameter::new(name.to_string(), None, false).expected(expected));
+        self
+    }
+
+    /// Parameter which takes a default value when not given
+    pub fn optional(mut self, name: &str, default: SymbolType, expected: ParameterType) -> Signature {
+        let default = Symbol::new(default, TokenPosition::internal());
+        self.parameters.push(Parameter::new(name.to_string(), Some(default), false).expected(expected));
+        self
+    }
+
+    /// Parameter which collects all remaining arguments
+    pub fn variadic(mut self, name: &str, expected: ParameterType) -> Signature {
+        self.parameters.push(Parameter::new(name.to_string(), None, true).expected(expected));
+        self
+    }
 }
\ No newline at end of file
@@ -0,0 +1,81 @@
+use crate::interpreter::symbol_table::SymbolTable;
+use crate::interpreter::symbols::{Symbol, SymbolType};
+use crate::errors::{Error, ErrorType};
+use crate::lexer::numbers::{self, Number};
+use crate::lexer::tokens::TokenPosition;
+
+/// Convert a number or string to an integer, truncating floats.
+/// Strings are read in the given base, and may start with the prefix
+/// of that base and be grouped with `_`, as in number literals.
+/// Example: int("0x1f", 16), int("-42"), int(2.5)
+pub fn int_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let base = match symbol_args[1].value {
+        SymbolType::Integer(base) if (2..=36).contains(&base) => base as u32,
+        ref base => return Err(argument_error(format!("Base must be between 2 and 36, got {}", base))),
+    };
+
+    let value = match symbol_args[0].value {
+        SymbolType::Integer(value) => value,
+        // outside of this range, and for NaN, the cast would saturate instead of failing
+        SymbolType::Float(value) if (-2_147_483_648.0..2_147_483_648.0).contains(&value.trunc()) => value as i32,
+        SymbolType::Float(value) => return Err(Error::new_runtime(
+            ErrorType::InvalidOperation,
+            format!("Cannot convert {} to an integer, as it is out of range", value),
+            &TokenPosition::internal(),
+        )),
+        SymbolType::String(ref text) => {
+            let (negative, digits) = split_sign(text.trim());
+
+            // the prefix is only allowed if it matches the base
+            let digits = match numbers::radix_of_prefix(digits) {
+                Some(radix) if radix == base => &digits[2..],
+                _ => digits,
+            };
+
+            match numbers::parse_signed_integer(digits, base, negative) {
+                Ok(value) => value,
+                Err(e) => return Err(argument_error(format!("Cannot convert \"{}\" to an integer: {}", text, e.message))),
+            }
+        },
+        ref value => return Err(argument_error(format!("Cannot convert {} to an integer", value.type_name()))),
+    };
+
+    Ok(Symbol::new(SymbolType::Integer(value), TokenPosition::internal()))
+}
+
+/// Convert a number or string to a float.
+/// Strings are read the same way as number literals.
+/// Example: float("1.5e-3"), float(2)
+pub fn float_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    let value = match symbol_args[0].value {
+        SymbolType::Integer(value) => value as f32,
+        SymbolType::Float(value) => value,
+        SymbolType::String(ref text) => {
+            let (negative, digits) = split_sign(text.trim());
+
+            let value = match numbers::parse_number(digits) {
+                Ok(Number::Integer(value)) => value as f32,
+                Ok(Number::Float(value)) => value,
+                Err(e) => return Err(argument_error(format!("Cannot convert \"{}\" to a float: {}", text, e.message))),
+            };
+
+            if negative { -value } else { value }
+        },
+        ref value => return Err(argument_error(format!("Cannot convert {} to a float", value.type_name()))),
+    };
+
+    Ok(Symbol::new(SymbolType::Float(value), TokenPosition::internal()))
+}
+
+/// Whether text starts with a minus sign, and the text after any sign
+fn split_sign(text: &str) -> (bool, &str) {
+    match text.chars().next() {
+        Some('-') => (true, &text[1..]),
+        Some('+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+fn argument_error(message: String) -> Error {
+    Error::new_runtime(ErrorType::ArgumentError, message, &TokenPosition::internal())
+}
\ No newline at end of file
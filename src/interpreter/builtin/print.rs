@@ -2,7 +2,6 @@ use crate::interpreter::symbol_table::SymbolTable;
 use crate::interpreter::symbols::{Symbol, SymbolType};
 use crate::errors::{Error, ErrorType};
 use crate::lexer::tokens::TokenPosition;
-use crate::parser::nodes::Node;
 
 /// Print a formatted string to the console.
 /// Examples:
@@ -11,9 +10,10 @@ use crate::parser::nodes::Node;
 /// 
 /// INPUT: print("{}, {}!", "Hello", "world")
 /// OUTPUT: Hello, world!
-pub fn print_func(symbol_table: &mut SymbolTable, args: &Vec<Node>) -> Result<Symbol, Error> {
-    let symbol_args = super::evaluate_args(symbol_table, args)?;
-
+/// 
+/// INPUT: print("Hello!")
+/// OUTPUT: Hello!
+pub fn print_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
     let text = match symbol_args[0].value {
         SymbolType::String(ref text) => text.clone(),
         _ => return Err(Error::new_runtime(
@@ -43,15 +43,16 @@ pub fn print_func(symbol_table: &mut SymbolTable, args: &Vec<Node>) -> Result<Sy
 /// Format a string with the given arguments
 fn format_string(text: String, args: Vec<String>) -> Result<String, String> {
     if !same_size(&text, &args) {
-        return Err(format!("Number of format arguments does not match number of format specifiers in string"));
+        return Err("Number of format arguments does not match number of format specifiers in string".to_string());
     };
 
-    let split_text_iter = text.split("{}");
+    let mut split_text_iter = text.split("{}");
     let args_iter = args.iter();
 
-    let mut final_string = String::new();
+    // text before the first format specifier
+    let mut final_string = split_text_iter.next().unwrap_or("").to_string();
 
-    for i in split_text_iter.zip(args_iter) {
+    for i in args_iter.zip(split_text_iter) {
         final_string += &(i.0.to_owned() + i.1);
     };
 
@@ -60,7 +61,7 @@ fn format_string(text: String, args: Vec<String>) -> Result<String, String> {
 
 
 /// Check if the number of format specifiers in the string is the same as the number of arguments
-fn same_size(text: &String, args: &Vec<String>) -> bool {
+fn same_size(text: &str, args: &[String]) -> bool {
     let split_text_iter = text.split("{}").count();
     let args_iter = args.len();
 
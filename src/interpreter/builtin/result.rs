@@ -0,0 +1,22 @@
+use crate::interpreter::symbol_table::SymbolTable;
+use crate::interpreter::symbols::{Symbol, SymbolType};
+use crate::errors::Error;
+use crate::lexer::tokens::TokenPosition;
+
+/// Wrap a value in a successful result.
+/// Example: Ok(5)
+pub fn ok_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    Ok(Symbol::new(SymbolType::Ok(Box::new(symbol_args[0].clone())), TokenPosition::internal()))
+}
+
+/// Wrap a value in a failed result.
+/// Example: Err("invalid number")
+pub fn err_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    Ok(Symbol::new(SymbolType::Err(Box::new(symbol_args[0].clone())), TokenPosition::internal()))
+}
+
+/// Wrap a value in an option.
+/// Example: Some(5)
+pub fn some_func(_symbol_table: &mut SymbolTable, symbol_args: &[Symbol]) -> Result<Symbol, Error> {
+    Ok(Symbol::new(SymbolType::Some(Box::new(symbol_args[0].clone())), TokenPosition::internal()))
+}
\ No newline at end of file
@@ -0,0 +1,306 @@
+//! Lazy iterators, which produce one value at a time when asked.
+//! Lists and tuples are iterated by `for` loops and built-ins through
+//! the same protocol, so they can be used wherever an iterator is.
+//!
+//! Generators are functions containing `yield`. Calling one returns an
+//! iterator without running the function; each value asked for runs the
+//! function until its next `yield`. The interpreter evaluates nodes
+//! recursively, so a generator keeps its own stack of the statements and
+//! loops it is within, and its local variables, between values.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::{symbol_table::SymbolTable, symbols::{ModuleSymbol, Symbol, SymbolType}};
+use crate::{
+    errors::{Error, ErrorType},
+    lexer::tokens::TokenPosition,
+    parser::nodes::{Node, NodeVisit, Pattern},
+};
+
+/// Iterator shared by every symbol holding it, so values are only produced once
+pub type SharedIterator = Rc<RefCell<IteratorState>>;
+
+#[derive(Debug)]
+pub enum IteratorState {
+    /// Elements of a list or tuple
+    Items { items: Vec<Symbol>, index: usize },
+    /// Integers from `next` up to, but not including, `stop`
+    Range { next: i32, stop: i32, step: i32 },
+    /// At most `remaining` values of another iterator
+    Take { inner: SharedIterator, remaining: usize },
+    /// Every value of each iterator in turn
+    Chain { iterators: Vec<SharedIterator>, index: usize },
+    Generator(Box<Generator>),
+}
+
+/// Suspended generator function
+#[derive(Debug)]
+pub struct Generator {
+    /// Local variables of the function
+    symbols: HashMap<String, Symbol>,
+    constants: HashMap<String, TokenPosition>,
+    /// Module the function was imported from, whose names it can see
+    module: Option<Rc<ModuleSymbol>>,
+    /// Call which created the generator
+    position: TokenPosition,
+    /// Statements and loops being run, innermost last.
+    /// Empty once the function has finished.
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug)]
+enum Frame {
+    Statements { statements: Rc<Vec<Node>>, index: usize },
+    Loop { pattern: Pattern, iterator: SharedIterator, body: Rc<Vec<Node>> },
+}
+
+/// What a generator does after looking at its innermost frame
+enum Step {
+    Push(Frame),
+    Pop,
+    Run(Node),
+}
+
+impl IteratorState {
+    pub fn shared(self) -> SharedIterator {
+        Rc::new(RefCell::new(self))
+    }
+
+    /// Produce the next value, or None once the iterator is exhausted
+    pub fn next(&mut self, symbol_table: &SymbolTable) -> Result<Option<Symbol>, Error> {
+        match self {
+            IteratorState::Items { items, index } => {
+                let item = items.get(*index).cloned();
+                *index += 1;
+                Ok(item)
+            },
+            IteratorState::Range { next, stop, step } => {
+                let finished = match *step > 0 {
+                    true => *next >= *stop,
+                    false => *next <= *stop,
+                };
+
+                if finished {
+                    return Ok(None);
+                }
+
+                let value = *next;
+                *next += *step;
+                Ok(Some(Symbol::new(SymbolType::Integer(value), TokenPosition::internal())))
+            },
+            IteratorState::Take { inner, remaining } => {
+                if *remaining == 0 {
+                    return Ok(None);
+                }
+
+                *remaining -= 1;
+                next(inner, symbol_table)
+            },
+            IteratorState::Chain { iterators, index } => {
+                while let Some(iterator) = iterators.get(*index) {
+                    if let Some(value) = next(iterator, symbol_table)? {
+                        return Ok(Some(value));
+                    }
+                    *index += 1;
+                }
+
+                Ok(None)
+            },
+            IteratorState::Generator(generator) => generator.resume(symbol_table),
+        }
+    }
+}
+
+/// Produce the next value of a shared iterator
+pub fn next(iterator: &SharedIterator, symbol_table: &SymbolTable) -> Result<Option<Symbol>, Error> {
+    match iterator.try_borrow_mut() {
+        Ok(mut iterator) => iterator.next(symbol_table),
+        Err(_) => Err(Error::new_runtime(
+            ErrorType::Exception,
+            "Iterator is already running".to_string(),
+            &TokenPosition::internal()
+        )),
+    }
+}
+
+/// Produce every remaining value of an iterator
+pub fn collect(iterator: &SharedIterator, symbol_table: &SymbolTable) -> Result<Vec<Symbol>, Error> {
+    let mut values: Vec<Symbol> = Vec::new();
+
+    while let Some(value) = next(iterator, symbol_table)? {
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+impl Symbol {
+    /// Iterator over the values of a list, tuple or iterator.
+    /// Returns None if the value cannot be iterated over.
+    pub fn iterate(&self) -> Option<SharedIterator> {
+        match self.value {
+            SymbolType::List(ref items) | SymbolType::Tuple(ref items) => Some(IteratorState::Items { items: items.clone(), index: 0 }.shared()),
+            SymbolType::Iterator(ref iterator) => Some(iterator.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Generator {
+    /// Generator which has not started running a function body.
+    /// `symbols` holds the arguments the function was called with.
+    pub fn new(body: &Node, symbols: HashMap<String, Symbol>, module: Option<Rc<ModuleSymbol>>, position: TokenPosition) -> Generator {
+        Generator {
+            symbols,
+            constants: HashMap::new(),
+            module,
+            position,
+            frames: vec![Frame::Statements { statements: statements_of(body), index: 0 }],
+        }
+    }
+
+    /// Run the function until its next yield, with its local variables
+    /// on top of the symbol table asking for the value
+    fn resume(&mut self, symbol_table: &SymbolTable) -> Result<Option<Symbol>, Error> {
+        let module_table;
+        let parent = match self.module {
+            Some(ref module) => {
+                module_table = SymbolTable::with_symbols(Box::new(symbol_table), module.symbols.clone());
+                &module_table
+            },
+            None => symbol_table,
+        };
+
+        let mut local_table = SymbolTable::with_symbols(Box::new(parent), std::mem::take(&mut self.symbols));
+        local_table.constants = std::mem::take(&mut self.constants);
+
+        // resuming runs the function again, so counts as a call
+        let result = match local_table.enter_call(&self.position).and_then(|_| self.run(&mut local_table)) {
+            // `?` returns from the function, finishing the generator
+            Err(e) if e.is_early_return() => {
+                self.frames.clear();
+                Ok(None)
+            },
+            result => result,
+        };
+
+        self.symbols = local_table.symbols;
+        self.constants = local_table.constants;
+
+        // an error finishes the generator
+        if result.is_err() {
+            self.frames.clear();
+        }
+
+        match self.module {
+            Some(ref module) => result.map_err(|e| e.with_file(&module.file)),
+            None => result,
+        }
+    }
+
+    fn run(&mut self, symbol_table: &mut SymbolTable) -> Result<Option<Symbol>, Error> {
+        loop {
+            let step = match self.frames.last_mut() {
+                None => return Ok(None),
+                Some(Frame::Statements { statements, index }) => match statements.get(*index) {
+                    Some(statement) => {
+                        *index += 1;
+                        Step::Run(statement.clone())
+                    },
+                    None => Step::Pop,
+                },
+                Some(Frame::Loop { pattern, iterator, body }) => match next(iterator, symbol_table)? {
+                    Some(item) => {
+                        pattern.bind(item, symbol_table)?;
+                        Step::Push(Frame::Statements { statements: body.clone(), index: 0 })
+                    },
+                    None => Step::Pop,
+                },
+            };
+
+            let statement = match step {
+                Step::Push(frame) => {
+                    self.frames.push(frame);
+                    continue;
+                },
+                Step::Pop => {
+                    self.frames.pop();
+                    continue;
+                },
+                Step::Run(statement) => statement,
+            };
+
+            match statement {
+                Node::YieldNode(ref node) => return node.value.visit(symbol_table).map(Some),
+
+                // loops are run by the generator, so they can yield
+                Node::ForExprNode(ref node) => {
+                    let iterable = node.iterable.visit(symbol_table)?;
+                    let iterator = match iterable.iterate() {
+                        Some(iterator) => iterator,
+                        None => return Err(Error::new_runtime(
+                            ErrorType::TypeError,
+                            format!("Cannot iterate over {}", iterable.value.type_name()),
+                            &node.iterable.get_position()
+                        )),
+                    };
+
+                    self.frames.push(Frame::Loop {
+                        pattern: node.pattern.clone(),
+                        iterator,
+                        body: statements_of(&node.body),
+                    });
+                },
+
+                // returning finishes the generator
+                Node::ReturnNode(ref node) => {
+                    if let Some(ref value) = node.value {
+                        value.visit(symbol_table)?;
+                    }
+                    self.frames.clear();
+                },
+
+                // branches which yield are run by the generator, like loops
+                Node::IfExprNode(ref node) if contains_yield(&statement) => {
+                    let condition = node.condition.visit(symbol_table)?;
+                    let branch = match condition.value {
+                        SymbolType::Integer(0) => node.if_false.as_ref(),
+                        SymbolType::Integer(_) => Some(&node.if_true),
+                        _ => return Err(Error::new_runtime(
+                            ErrorType::Exception,
+                            "If statement condition must be an integer".to_string(),
+                            &node.get_position()
+                        )),
+                    };
+
+                    if let Some(branch) = branch {
+                        self.frames.push(Frame::Statements { statements: statements_of(branch), index: 0 });
+                    }
+                },
+
+                ref statement => {
+                    statement.visit(symbol_table)?;
+                },
+            }
+        }
+    }
+}
+
+/// Whether a function body contains a yield, making the function a generator.
+/// Yields in nested function definitions belong to those functions.
+pub fn contains_yield(body: &Node) -> bool {
+    match body {
+        Node::StatementsNode(statements) => statements.statements.iter().any(contains_yield),
+        Node::ForExprNode(node) => contains_yield(&node.body),
+        Node::IfExprNode(node) => contains_yield(&node.if_true) || node.if_false.as_ref().is_some_and(contains_yield),
+        Node::YieldNode(_) => true,
+        _ => false,
+    }
+}
+
+fn statements_of(body: &Node) -> Rc<Vec<Node>> {
+    match body {
+        Node::StatementsNode(statements) => Rc::new(statements.statements.clone()),
+        other => Rc::new(vec![other.clone()]),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,71 @@
+//! Methods which can be called on symbols with `value.method(args)`.
+//! Methods are grouped by the type of symbol they apply to.
+
+pub mod result; // Result and option methods
+pub mod module; // Module members
+
+use crate::errors::{self, Error, ErrorType};
+use crate::lexer::tokens::TokenPosition;
+
+use super::symbol_table::SymbolTable;
+use super::symbols::{Symbol, SymbolType};
+
+impl Symbol {
+    /// Call a method on this symbol with already evaluated arguments
+    pub fn call_method(&self, name: &str, args: Vec<Symbol>, kwargs: Vec<(String, Symbol)>, symbol_table: &mut SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        // only functions defined in Yum take keyword arguments
+        if let SymbolType::Module(_) = self.value {
+            return self.call_module_function(name, args, kwargs, symbol_table, position);
+        }
+
+        if !kwargs.is_empty() {
+            return Err(Error::new_runtime(
+                ErrorType::ArgumentError,
+                format!("Method '{}' does not take keyword arguments", name),
+                position
+            ));
+        }
+
+        let (result, methods) = match self.value {
+            SymbolType::Ok(_) | SymbolType::Err(_) |
+            SymbolType::Some(_) | SymbolType::None => (self.call_result_method(name, &args, symbol_table, position), result::RESULT_METHODS.as_slice()),
+            _ => (None, [].as_slice()),
+        };
+
+        match result {
+            Some(result) => result,
+            None => Err(Error::new_runtime(
+                ErrorType::InvalidOperation,
+                format!("Unknown method '{}' for {}", name, self.value),
+                position
+            ).with_suggestions(name, methods.iter().copied()))
+        }
+    }
+}
+
+impl Symbol {
+    /// Get an attribute of this symbol, e.g. `utils.pi`
+    pub fn get_attribute(&self, name: &str, position: &TokenPosition) -> Result<Symbol, Error> {
+        match self.value {
+            SymbolType::Module(_) => self.get_module_member(name, position),
+            _ => Err(Error::new_runtime(
+                ErrorType::InvalidOperation,
+                format!("Unknown attribute '{}' for {}", name, self.value),
+                position
+            ))
+        }
+    }
+}
+
+/// Ensure a method was called with the right number of arguments
+fn expect_args(name: &str, args: &[Symbol], count: usize, position: &TokenPosition) -> Result<(), Error> {
+    if args.len() != count {
+        return Err(Error::new_runtime(
+            ErrorType::ArgumentError,
+            format!("Method '{}' expected {}, got {}", name, errors::count(count, "argument"), args.len()),
+            position
+        ));
+    }
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+use crate::errors::{Error, ErrorType};
+use crate::lexer::tokens::TokenPosition;
+
+use super::super::symbol_table::SymbolTable;
+use super::super::symbols::{Symbol, SymbolType};
+
+impl Symbol {
+    /// Get a name defined by a module
+    pub fn get_module_member(&self, name: &str, position: &TokenPosition) -> Result<Symbol, Error> {
+        let module = match self.value {
+            SymbolType::Module(ref module) => module,
+            _ => panic!("Symbol must be a module"),
+        };
+
+        match module.get(name) {
+            Some(symbol) => Ok(symbol),
+            None => Err(Error::new_runtime(
+                ErrorType::ImportError,
+                format!("Module '{}' has no member '{}'", module.name, name),
+                position
+            ).with_suggestions(name, module.symbols.keys().map(String::as_str)))
+        }
+    }
+
+    /// Call a function defined by a module, e.g. `utils.f(x)`
+    pub fn call_module_function(&self, name: &str, args: Vec<Symbol>, kwargs: Vec<(String, Symbol)>, symbol_table: &mut SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        let member = self.get_module_member(name, position)?;
+
+        match member.value {
+            SymbolType::Function(ref func) => func.call_with_keywords(args, kwargs, symbol_table, position),
+            _ => Err(Error::new_runtime(
+                ErrorType::TypeError,
+                format!("Cannot call non-function {}", member.value),
+                position
+            ))
+        }
+    }
+}
\ No newline at end of file
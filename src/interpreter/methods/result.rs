@@ -0,0 +1,83 @@
+use crate::errors::{Error, ErrorType};
+use crate::lexer::tokens::TokenPosition;
+
+use super::expect_args;
+use super::super::operations::bool_to_int;
+use super::super::symbol_table::SymbolTable;
+use super::super::symbols::{Symbol, SymbolType};
+
+/// Names of the methods of results and options
+pub const RESULT_METHODS: [&str; 7] = ["is_ok", "is_err", "is_some", "is_none", "unwrap", "unwrap_or", "map"];
+
+impl Symbol {
+    /// Methods shared by results (Ok/Err) and options (Some/None).
+    /// Returns None if the method does not exist.
+    pub fn call_result_method(&self, name: &str, args: &[Symbol], symbol_table: &mut SymbolTable, position: &TokenPosition) -> Option<Result<Symbol, Error>> {
+        let result = match name {
+            "is_ok" => self.is_variant(name, args, position, matches!(self.value, SymbolType::Ok(_))),
+            "is_err" => self.is_variant(name, args, position, matches!(self.value, SymbolType::Err(_))),
+            "is_some" => self.is_variant(name, args, position, matches!(self.value, SymbolType::Some(_))),
+            "is_none" => self.is_variant(name, args, position, matches!(self.value, SymbolType::None)),
+            "unwrap" => self.unwrap(name, args, position),
+            "unwrap_or" => self.unwrap_or(name, args, position),
+            "map" => self.map(name, args, symbol_table, position),
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    /// is_ok, is_err, is_some and is_none
+    fn is_variant(&self, name: &str, args: &[Symbol], position: &TokenPosition, is_variant: bool) -> Result<Symbol, Error> {
+        expect_args(name, args, 0, position)?;
+        Ok(Symbol::new(SymbolType::Integer(bool_to_int(is_variant)), *position))
+    }
+
+    /// Get the inner value, failing on Err/None
+    fn unwrap(&self, name: &str, args: &[Symbol], position: &TokenPosition) -> Result<Symbol, Error> {
+        expect_args(name, args, 0, position)?;
+        match self.value {
+            SymbolType::Ok(ref value) | SymbolType::Some(ref value) => Ok(*value.clone()),
+            _ => Err(Error::new_runtime(
+                ErrorType::Exception,
+                format!("Called unwrap on {}", self.value),
+                position
+            ))
+        }
+    }
+
+    /// Get the inner value, or the default on Err/None
+    fn unwrap_or(&self, name: &str, args: &[Symbol], position: &TokenPosition) -> Result<Symbol, Error> {
+        expect_args(name, args, 1, position)?;
+        match self.value {
+            SymbolType::Ok(ref value) | SymbolType::Some(ref value) => Ok(*value.clone()),
+            _ => Ok(args[0].clone()),
+        }
+    }
+
+    /// Apply a function to the inner value of Ok/Some, leaving Err/None untouched
+    fn map(&self, name: &str, args: &[Symbol], symbol_table: &mut SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        expect_args(name, args, 1, position)?;
+
+        let func = match args[0].value {
+            SymbolType::Function(ref func) => func,
+            _ => return Err(Error::new_runtime(
+                ErrorType::TypeError,
+                format!("Cannot map with non-function {}", args[0].value),
+                position
+            ))
+        };
+
+        match self.value {
+            SymbolType::Ok(ref value) => {
+                let mapped = func.call(vec![*value.clone()], symbol_table, position)?;
+                Ok(Symbol::new(SymbolType::Ok(Box::new(mapped)), *position))
+            },
+            SymbolType::Some(ref value) => {
+                let mapped = func.call(vec![*value.clone()], symbol_table, position)?;
+                Ok(Symbol::new(SymbolType::Some(Box::new(mapped)), *position))
+            },
+            _ => Ok(self.clone()),
+        }
+    }
+}
\ No newline at end of file
@@ -3,10 +3,61 @@
 pub mod symbols;
 pub mod symbol_table;
 pub mod builtin;
+pub mod methods;
+pub mod modules;
+pub mod iterators;
+
+use std::cell::Cell;
 
 use symbol_table::SymbolTable;
+use symbols::{Symbol, SymbolType};
+
+use crate::{parser::nodes::{Node, NodeVisit}, errors::{Error, ErrorType}, lexer::tokens::TokenPosition};
+
+/// Most function calls which may be made within each other, unless changed
+/// with `Interpreter::set_max_call_depth`
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+/// Size of the stack programs should be parsed and run on. Nodes are visited
+/// recursively, taking kilobytes of stack for each function call and nested
+/// expression, so the main thread's stack runs out well before the deepest
+/// calls and expressions which are allowed.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
 
-use crate::{parser::nodes::{Node, NodeVisit}, errors::{Error}};
+/// Stack kept free for the call being made when calls are refused because
+/// the stack is nearly full. A call inside the most deeply nested expression
+/// allowed takes under half a megabyte in a debug build.
+const STACK_RESERVE: usize = 8 * 1024 * 1024;
+
+thread_local! {
+    /// Address near the start of the stack of a thread made by `with_stack`
+    static STACK_START: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Run `f` on a new thread with a stack of `STACK_SIZE` bytes, giving its result
+pub fn with_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        let thread = std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || {
+                let start = 0u8;
+                STACK_START.set(Some(std::ptr::addr_of!(start) as usize));
+                f()
+            })
+            .expect("Failed to start the interpreter thread");
+
+        thread.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Whether so little of the stack is left that another function call could
+/// overflow it, however high the maximum call depth is. Only known on
+/// threads made by `with_stack`.
+pub fn stack_is_nearly_full() -> bool {
+    let here = 0u8;
+    let here = std::ptr::addr_of!(here) as usize;
+    STACK_START.get().is_some_and(|start| start.abs_diff(here) > STACK_SIZE - STACK_RESERVE)
+}
 
 pub struct Interpreter<'a> {
     pub symbol_table: SymbolTable<'a>,
@@ -19,20 +70,377 @@ impl<'a> Interpreter<'a> {
         }
     }
 
+    /// Create an interpreter for a source file, which imports
+    /// are resolved relative to
+    pub fn with_file(file: &str) -> Interpreter<'a> {
+        let interpreter = Interpreter::new();
+        interpreter.symbol_table.modules.borrow_mut().set_main_file(file);
+        interpreter
+    }
+
+    /// Give the program the arguments it was run with, as the list `args`
+    pub fn set_args(&mut self, args: Vec<String>) {
+        let args = args.into_iter().map(|x| Symbol::new(SymbolType::String(x), TokenPosition::internal())).collect();
+        self.symbol_table.symbols.insert("args".to_string(), Symbol::new(SymbolType::List(args), TokenPosition::internal()));
+    }
+
+    /// Change how many function calls may be made within each other
+    /// before a `RecursionError`
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.symbol_table.max_call_depth = depth;
+    }
+
     pub fn run(&mut self, ast: Node) -> Result<(), Error> {
-        // first node must be a statements node
-        let statements = match ast {
-            Node::StatementsNode(statements) => *statements,
-            _ => panic!("First node must be a statements node"),
-        };
+        run_statements(ast, &mut self.symbol_table)
+    }
+}
 
-        // run all statements in order
-        for statement in statements.statements {
-            statement.visit(&mut self.symbol_table)?;
-        }
+impl<'a> Default for Interpreter<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        Ok(())
+/// Run all statements of a program or module in order
+pub fn run_statements(ast: Node, symbol_table: &mut SymbolTable) -> Result<(), Error> {
+    // first node must be a statements node
+    let statements = match ast {
+        Node::StatementsNode(statements) => *statements,
+        _ => panic!("First node must be a statements node"),
+    };
+
+    // run all statements in order
+    for statement in statements.statements {
+        run_statement(&statement, symbol_table)?;
     }
+
+    Ok(())
 }
 
-mod operations;
\ No newline at end of file
+/// Run a single top-level statement, giving its value
+pub fn run_statement(statement: &Node, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+    match statement.visit(symbol_table) {
+        // `?` and returns inside loops can only leave a function, not the program
+        Err(e) if e.is_early_return() => Err(Error::new_runtime(
+            ErrorType::Exception,
+            format!("Cannot return {} outside of a function", e.return_value.unwrap()),
+            &e.position,
+        )),
+        result => result,
+    }
+}
+
+mod operations;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::{Parser, MAX_NESTING}};
+
+    /// Run a program, giving its first syntax error if it has any
+    fn run(interpreter: &mut Interpreter, text: &str) -> Result<(), Error> {
+        let tokens = Lexer::new(text.to_string()).make_tokens()?;
+        let ast = Parser::new(tokens).parse().map_err(|mut errors| errors.remove(0))?;
+        interpreter.run(ast)
+    }
+
+    fn assert_recursion_error(result: Result<(), Error>, depth: usize) {
+        let error = result.unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::RecursionError), "{:?}", error);
+        // every call is in the traceback, along with the one which was refused
+        assert_eq!(error.frames().len(), depth + 1);
+    }
+
+    #[test]
+    fn integer_arithmetic_errors_instead_of_panicking() {
+        for source in ["1 / 0;", "2 ^ 40;", "2 ^ -1;", "2147483647 + 1;", "-2147483647 - 2;", "65536 * 65536;", "const A = 1 / 0;", "const A = 2 ^ 40;"] {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::InvalidOperation), "{}: {:?}", source, error);
+        }
+    }
+
+    #[test]
+    fn floats_out_of_range_of_an_integer_are_not_converted() {
+        for source in ["int(1e20);", "int(-1e20);", "int(2147483648.0);"] {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::InvalidOperation), "{}: {:?}", source, error);
+        }
+
+        run(&mut Interpreter::new(), "assert int(-2147483648.0) == -2147483647 - 1; assert int(-2.5) == -2;").unwrap();
+    }
+
+    #[test]
+    fn strings_convert_to_every_integer_in_range() {
+        run(&mut Interpreter::new(), "assert int(\"-2147483648\") == -2147483647 - 1; assert int(\"2147483647\") == 2147483647; assert int(\"-80000000\", 16) == -2147483647 - 1;").unwrap();
+
+        for (source, message) in [("int(\"2147483648\");", "too large"), ("int(\"-2147483649\");", "too small"), ("int(\"-99999999999\");", "too small")] {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(error.error_message.contains(message), "{}: {:?}", source, error);
+        }
+    }
+
+    #[test]
+    fn exit_codes_must_fit_in_a_byte() {
+        for source in ["exit(256);", "exit(-1);"] {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::ArgumentError), "{}: {:?}", source, error);
+        }
+
+        for code in [0, 3, 255] {
+            let error = run(&mut Interpreter::new(), &format!("exit({});", code)).unwrap_err();
+            assert_eq!(error.exit_code(), code);
+        }
+    }
+
+    #[test]
+    fn missing_module_members_are_import_errors() {
+        for source in ["import \"math_utils.yum\" as m; m.duble(1);", "from \"math_utils.yum\" import duble;"] {
+            let error = run(&mut Interpreter::with_file("test_code/main.yum"), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::ImportError), "{}: {:?}", source, error);
+            assert_eq!(error.notes, ["did you mean `double`?"]);
+        }
+    }
+
+    #[test]
+    fn cyclic_imports_are_import_errors() {
+        let error = run(&mut Interpreter::with_file("test_code/main.yum"), "import \"cycle_a.yum\";").unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ImportError), "{:?}", error);
+        assert_eq!(error.error_message, "Cyclic import: test_code/cycle_a.yum -> test_code/cycle_b.yum -> test_code/cycle_a.yum");
+        assert_eq!(error.file.as_ref().unwrap().name, "test_code/cycle_b.yum");
+
+        // importing the file being run is a cycle too
+        let error = run(&mut Interpreter::with_file("test_code/cycle_a.yum"), "import \"cycle_b.yum\";").unwrap_err();
+        assert_eq!(error.error_message, "Cyclic import: test_code/cycle_a.yum -> test_code/cycle_b.yum -> test_code/cycle_a.yum");
+    }
+
+    #[test]
+    fn modules_are_evaluated_once() {
+        // both imports share the module's iterator, so taking from one advances the other
+        let source = "import \"shared_iterator.yum\" as a; import \"shared_iterator.yum\" as b; from \"shared_iterator.yum\" import numbers;
+            assert [x for x in take(a.numbers, 1)] == [0];
+            assert [x for x in take(b.numbers, 1)] == [1];
+            assert [x for x in numbers] == [2];";
+        run(&mut Interpreter::with_file("test_code/main.yum"), source).unwrap();
+    }
+
+    #[test]
+    fn generators_only_run_as_far_as_their_values_are_used() {
+        // the division by zero is never reached
+        let source = "func numbers() { yield 1; yield 2; yield 1 / 0; };
+            assert [x for x in take(numbers(), 2)] == [1, 2];
+            func naturals() { for i in range(2147483647) { yield i; }; };
+            assert [x for x in take(naturals(), 3)] == [0, 1, 2];";
+        run(&mut Interpreter::new(), source).unwrap();
+
+        let error = run(&mut Interpreter::new(), "func numbers() { yield 1; yield 1 / 0; }; [x for x in numbers()];").unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::InvalidOperation), "{:?}", error);
+    }
+
+    #[test]
+    fn generators_resume_where_they_left_off() {
+        let source = "func totals(xs) { let total = 0; for x in xs { let total += x; yield total; }; };
+            let running = totals([1, 2, 3, 4]);
+            assert [x for x in take(running, 2)] == [1, 3];
+            assert [x for x in running] == [6, 10];
+            assert [x for x in running] == [];
+            assert [x for x in totals([5])] == [5];";
+        run(&mut Interpreter::new(), source).unwrap();
+    }
+
+    #[test]
+    fn arguments_are_bound_by_position_keyword_and_default() {
+        let source = "func f(a, b = 2, *rest) { return (a, b, rest); };
+            assert f(1) == (1, 2, []);
+            assert f(1, 3) == (1, 3, []);
+            assert f(1, 3, 4, 5) == (1, 3, [4, 5]);
+            assert f(b: 3, a: 1) == (1, 3, []);";
+        run(&mut Interpreter::new(), source).unwrap();
+    }
+
+    #[test]
+    fn arguments_which_do_not_match_the_parameters_are_errors() {
+        let examples = [
+            ("func f(a, b = 2) { return a; }; f();", "Missing argument 'a'"),
+            ("func f(a, b = 2) { return a; }; f(1, 2, 3);", "Expected at most 2 arguments, got 3"),
+            ("func f(a, b = 2) { return a; }; f(1, c: 3);", "Unexpected keyword argument 'c'"),
+            ("func f(a, b = 2) { return a; }; f(1, a: 3);", "Got multiple values for argument 'a'"),
+            ("func f(a, *rest) { return a; }; f(1, rest: 2);", "Unexpected keyword argument 'rest'"),
+        ];
+
+        for (source, message) in examples {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::ArgumentError), "{}: {:?}", source, error);
+            assert_eq!(error.error_message, message, "{}", source);
+        }
+    }
+
+    #[test]
+    fn imported_modules_are_checked() {
+        let tokens = Lexer::new("func f() { import \"error_in_module.yum\"; };".to_string()).make_tokens().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+
+        let errors = modules::check_imports(&ast, "test_code/type_errors/main.yum").unwrap_err();
+        assert_eq!(errors[0].error_message, "Cannot add string and int");
+        assert_eq!(errors[0].file.as_ref().unwrap().name, "test_code/type_errors/error_in_module.yum");
+
+        let tokens = Lexer::new("import \"math_utils.yum\"; import \"missing.yum\";".to_string()).make_tokens().unwrap();
+        modules::check_imports(&Parser::new(tokens).parse().unwrap(), "test_code/main.yum").unwrap();
+    }
+
+    #[test]
+    fn type_names_match_the_annotations() {
+        let error = run(&mut Interpreter::new(), "\"a\" + 1;").unwrap_err();
+        assert_eq!(error.error_message, "Cannot add string and int");
+
+        let error = run(&mut Interpreter::new(), "int(\"1\", \"x\");").unwrap_err();
+        assert_eq!(error.error_message, "Expected argument 'base' of type int, found string");
+    }
+
+    #[test]
+    fn annotations_are_checked_when_running() {
+        let error = run(&mut Interpreter::with_file("test_code/main.yum"), "from \"math_utils.yum\" import double; let s: string = double(1);").unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::TypeError), "{:?}", error);
+        assert_eq!(error.error_message, "Cannot assign int to 's' declared as string");
+
+        let error = run(&mut Interpreter::new(), "func f(x: int) { return x; }; f(\"a\");").unwrap_err();
+        assert_eq!(error.error_message, "Expected argument 'x' of type int, found string");
+
+        let error = run(&mut Interpreter::new(), "func f(x) -> int { return x; }; f(\"a\");").unwrap_err();
+        assert_eq!(error.error_message, "Function 'f' should return int, found string");
+
+        run(&mut Interpreter::new(), "func f(x: list, y: option) -> iterator { return range(2); }; let i: iterator = f([1], None);").unwrap();
+    }
+
+    #[test]
+    fn integers_become_floats_where_floats_are_annotated() {
+        let source = "func half(x: float) -> float { return x / 2; };
+            assert half(3) == 1.5;
+            let y: float = 1; assert y / 2 == 0.5;
+            func one() -> float { return 1; }; assert one() / 2 == 0.5;
+            func halves(*xs: float) { return [x / 2 for x in xs]; }; assert halves(1, 2.0) == [0.5, 1.0];";
+        run(&mut Interpreter::new(), source).unwrap();
+    }
+
+    #[test]
+    fn builtins_can_be_shadowed_inside_functions() {
+        let source = "func f(range) { return range + 1; }; assert f(1) == 2;
+            func g() { let all = 1; return all; }; assert g() == 1;
+            func h(xs) { for input in xs { return input; }; }; assert h([3]) == 3;
+            assert [filter for filter in [4]] == [4];
+            func k(p) { let (take, x) = p; return take + x; }; assert k((1, 2)) == 3;
+            assert [x for x in range(2)] == [0, 1];";
+        run(&mut Interpreter::new(), source).unwrap();
+
+        let error = run(&mut Interpreter::new(), "let range = 1;").unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::ConstantError), "{:?}", error);
+    }
+
+    #[test]
+    fn values_are_compared_element_by_element() {
+        let source = "assert (1, 2) == (1, 2); assert [1] in [[1], [2]]; assert (1, 2) in [(1, 2)]; assert Some(1) in [Some(1)]; assert Ok(1) != Err(1); assert [[1]] != [[1, 2]];";
+        run(&mut Interpreter::new(), source).unwrap();
+    }
+
+    #[test]
+    fn values_which_cannot_be_compared_are_an_error() {
+        for source in ["\"a\" in [1];", "[1] == [\"a\"];", "(1, 2) == 1;"] {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::TypeError), "{}: {:?}", source, error);
+        }
+    }
+
+    const ENDLESS: &str = "func f(n) { return f(n + 1); }; f(0);";
+
+    #[test]
+    fn endless_recursion_is_a_recursion_error() {
+        with_stack(|| assert_recursion_error(run(&mut Interpreter::new(), ENDLESS), DEFAULT_MAX_CALL_DEPTH));
+    }
+
+    #[test]
+    fn endless_generators_are_a_recursion_error() {
+        let source = "func g() { for x in g() { yield x; }; }; for x in g() { print(\"{}\", x); };";
+        with_stack(|| {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::RecursionError), "{:?}", error);
+        });
+    }
+
+    #[test]
+    fn max_call_depth_can_be_changed() {
+        let source = "func f(n) { return if n == 0 { 0 } else { f(n - 1) }; };";
+
+        with_stack(|| {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_max_call_depth(10);
+            run(&mut interpreter, source).unwrap();
+
+            run(&mut interpreter, "f(9);").unwrap();
+            assert_recursion_error(run(&mut interpreter, "f(10);"), 10);
+            assert_recursion_error(run(&mut interpreter, ENDLESS), 10);
+        });
+    }
+
+    #[test]
+    fn calls_stop_before_the_stack_overflows() {
+        // each call is inside the most deeply nested expression allowed, so takes a lot of stack
+        let nested = format!("{}f(n - 1){}", "(".repeat(MAX_NESTING - 10), " + 1)".repeat(MAX_NESTING - 10));
+        let source = format!("func f(n) {{ return if n == 0 {{ 0 }} else {{ {} }}; }}; f(100000);", nested);
+
+        with_stack(|| {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_max_call_depth(usize::MAX);
+
+            let error = run(&mut interpreter, &source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::RecursionError), "{:?}", error);
+            assert!(error.error_message.starts_with("Stack is full"), "{:?}", error);
+        });
+    }
+
+    /// Functions called in each cycle of calls in the traceback of an error,
+    /// and how many more times the cycle was repeated
+    fn cycles(error: &Error) -> Vec<(Vec<&str>, usize)> {
+        error.calls().into_iter()
+            .map(|(cycle, repeats)| (cycle.iter().map(|x| x.function.as_str()).collect(), repeats))
+            .collect()
+    }
+
+    #[test]
+    fn recursive_calls_are_collapsed_in_tracebacks() {
+        let source = "func count(n) { return if n == 0 { int(\"x\") } else { count(n - 1) }; }; func start() { return count(5); }; start();";
+        let error = run(&mut Interpreter::new(), source).unwrap_err();
+
+        assert_eq!(cycles(&error), [(vec!["start"], 0), (vec!["count"], 0), (vec!["count"], 4)]);
+    }
+
+    #[test]
+    fn mutually_recursive_calls_are_collapsed_in_tracebacks() {
+        let source = "func is_even(n) { return if n == 0 { int(\"x\") } else { is_odd(n - 1) }; }; func is_odd(n) { return if n == 0 { 0 } else { is_even(n - 1) }; }; is_even(6);";
+        let error = run(&mut Interpreter::new(), source).unwrap_err();
+
+        // the first call is made from the program, rather than from is_odd
+        assert_eq!(cycles(&error), [(vec!["is_even"], 0), (vec!["is_odd", "is_even"], 2)]);
+        assert!(error.to_string().ends_with("... last 2 calls repeated 2 more times"), "{}", error);
+    }
+
+    #[test]
+    fn endless_mutual_recursion_is_collapsed_in_tracebacks() {
+        let source = "func a(n) { return b(n); }; func b(n) { return a(n); }; a(0);";
+        with_stack(|| {
+            let error = run(&mut Interpreter::new(), source).unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::RecursionError), "{:?}", error);
+            // a is called from the program, then b and a take turns for the other 1000 calls
+            assert_eq!(cycles(&error), [(vec!["a"], 0), (vec!["b", "a"], DEFAULT_MAX_CALL_DEPTH / 2 - 1)]);
+        });
+    }
+
+    #[test]
+    fn interpreter_keeps_running_after_a_recursion_error() {
+        with_stack(|| {
+            let mut interpreter = Interpreter::new();
+            assert_recursion_error(run(&mut interpreter, ENDLESS), DEFAULT_MAX_CALL_DEPTH);
+
+            // calls made before the error no longer count
+            assert_eq!(interpreter.symbol_table.call_depth, 0);
+            run(&mut interpreter, "func g(n) { return if n == 0 { 0 } else { g(n - 1) }; }; assert g(900) == 0;").unwrap();
+        });
+    }
+}
@@ -0,0 +1,195 @@
+//! Loads modules imported with `import "file.yum" as name;` or
+//! `from "file.yum" import a, b;`.
+//! Each file is evaluated once into its own namespace, and later
+//! imports of the same file share that namespace.
+
+use std::{collections::HashMap, fs, path::{Path, PathBuf}, rc::Rc};
+
+use std::collections::HashSet;
+
+use super::{run_statements, symbol_table::SymbolTable, symbols::ModuleSymbol};
+use crate::{
+    checker, resolver,
+    errors::{Error, ErrorType, SourceFile},
+    lexer::{Lexer, tokens::{TokenPosition, TokenType}},
+    parser::{Parser, nodes::{ImportNode, Node}},
+};
+
+#[derive(Debug, Default)]
+pub struct ModuleLoader {
+    /// Modules which have been evaluated, by canonical path
+    loaded: HashMap<PathBuf, Rc<ModuleSymbol>>,
+    /// Files currently being evaluated as (canonical path, file name).
+    /// The last one is the file whose imports are being resolved.
+    loading: Vec<(PathBuf, String)>,
+}
+
+impl ModuleLoader {
+    pub fn new() -> ModuleLoader {
+        ModuleLoader::default()
+    }
+
+    /// Set the file being run so that its imports are resolved
+    /// relative to it, and importing it again is detected as a cycle
+    pub fn set_main_file(&mut self, file: &str) {
+        let canonical = fs::canonicalize(file).unwrap_or_else(|_| PathBuf::from(file));
+        self.loading = vec![(canonical, file.to_string())];
+    }
+
+    /// Resolve an import path relative to the importing file.
+    /// Returns the canonical path and the file name to show in errors.
+    fn resolve(&self, path: &str, position: &TokenPosition) -> Result<(PathBuf, String), Error> {
+        let file = relative_to(self.loading.last().map(|(_, importer)| importer.as_str()), path);
+
+        match fs::canonicalize(&file) {
+            Ok(canonical) => Ok((canonical, file.display().to_string())),
+            Err(e) => Err(Error::new_runtime(
+                ErrorType::ImportError,
+                format!("Cannot find module '{}': {}", file.display(), e),
+                position
+            )),
+        }
+    }
+
+    /// Describe the chain of imports which leads back to `canonical`
+    fn cycle(&self, canonical: &Path, file: &str) -> Option<String> {
+        let start = self.loading.iter().position(|(path, _)| path == canonical)?;
+
+        let mut chain: Vec<&str> = self.loading[start..].iter().map(|(_, name)| name.as_str()).collect();
+        chain.push(file);
+
+        Some(chain.join(" -> "))
+    }
+}
+
+/// Path of an imported file, relative to the file importing it
+fn relative_to(importer: Option<&str>, path: &str) -> PathBuf {
+    match importer {
+        Some(importer) => Path::new(importer).parent().unwrap_or(Path::new("")).join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Name a module is bound to when imported without an alias
+pub fn module_name(path: &str) -> String {
+    Path::new(path).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or(path.to_string())
+}
+
+/// First of the syntax errors in a module, saying how many others there are
+fn first_error(errors: Vec<Error>) -> Error {
+    let others = errors.len().saturating_sub(1);
+    let first = errors.into_iter().min_by_key(|e| (e.position.line, e.position.column)).expect("Expected a syntax error");
+
+    match others {
+        0 => first,
+        1 => first.with_note("the module has 1 other syntax error".to_string()),
+        _ => first.with_note(format!("the module has {} other syntax errors", others)),
+    }
+}
+
+/// Load a module, evaluating it if it has not been loaded yet.
+/// Its code counts towards the calls being made within `symbol_table`.
+pub fn load_module(symbol_table: &SymbolTable, path: &str, position: &TokenPosition) -> Result<Rc<ModuleSymbol>, Error> {
+    let modules = &symbol_table.modules;
+    let (canonical, file) = modules.borrow().resolve(path, position)?;
+
+    if let Some(module) = modules.borrow().loaded.get(&canonical) {
+        return Ok(module.clone());
+    }
+
+    if let Some(cycle) = modules.borrow().cycle(&canonical, &file) {
+        return Err(Error::new_runtime(
+            ErrorType::ImportError,
+            format!("Cyclic import: {}", cycle),
+            position
+        ));
+    }
+
+    let source = match fs::read_to_string(&canonical) {
+        Ok(source) => source,
+        Err(e) => return Err(Error::new_runtime(
+            ErrorType::IOError,
+            format!("Error while reading module '{}': {}", file, e),
+            position
+        )),
+    };
+
+    let source_file = SourceFile::new(&file, &source);
+    let tokens = Lexer::new(source).make_tokens().map_err(|e| e.with_file(&source_file))?;
+    let ast = Parser::new(tokens).parse().map_err(|errors| first_error(errors).with_file(&source_file))?;
+
+    // evaluate the module in its own namespace, on top of the builtins
+    modules.borrow_mut().loading.push((canonical.clone(), file.clone()));
+
+    let mut builtins = SymbolTable::new_global_with_modules(modules.clone());
+    builtins.call_depth = symbol_table.call_depth;
+    builtins.max_call_depth = symbol_table.max_call_depth;
+    let mut module_table = SymbolTable::new(Box::new(&builtins));
+    let result = run_statements(ast, &mut module_table);
+
+    modules.borrow_mut().loading.pop();
+    result.map_err(|e| e.with_file(&source_file))?;
+
+    let module = Rc::new(ModuleSymbol::new(module_name(&file), source_file, module_table.symbols));
+
+    modules.borrow_mut().loaded.insert(canonical, module.clone());
+    Ok(module)
+}
+/// Import statements of a program or module, including those in
+/// function bodies, branches and loops
+fn imports<'a>(node: &'a Node, found: &mut Vec<&'a ImportNode>) {
+    match node {
+        Node::StatementsNode(statements) => for statement in &statements.statements {
+            imports(statement, found);
+        },
+        Node::ImportNode(import) => found.push(import),
+        Node::FuncDefNode(func_def) => if let Some(ref body) = func_def.body {
+            imports(body, found);
+        },
+        Node::IfExprNode(if_expr) => {
+            imports(&if_expr.if_true, found);
+            if let Some(ref if_false) = if_expr.if_false {
+                imports(if_false, found);
+            }
+        },
+        Node::ForExprNode(for_expr) => imports(&for_expr.body, found),
+        _ => {},
+    }
+}
+
+/// Resolve and check the types of every module a program imports, and the
+/// modules they import, each once. Errors are given in the module's file.
+/// Modules which cannot be found or read are left to fail when imported.
+pub fn check_imports(ast: &Node, file: &str) -> Result<(), Vec<Error>> {
+    let mut checked: HashSet<PathBuf> = HashSet::new();
+    checked.extend(fs::canonicalize(file));
+    check_imports_of(ast, file, &mut checked)
+}
+
+fn check_imports_of(ast: &Node, file: &str, checked: &mut HashSet<PathBuf>) -> Result<(), Vec<Error>> {
+    let mut found: Vec<&ImportNode> = Vec::new();
+    imports(ast, &mut found);
+
+    for import in found {
+        let TokenType::String(ref path) = import.path.value else { continue };
+        let module = relative_to(Some(file), path);
+
+        let Ok(canonical) = fs::canonicalize(&module) else { continue };
+        if !checked.insert(canonical) {
+            continue;
+        }
+
+        let Ok(source) = fs::read_to_string(&module) else { continue };
+        let source_file = SourceFile::new(&module.display().to_string(), &source);
+        let in_module = |errors: Vec<Error>| errors.into_iter().map(|e| e.with_file(&source_file)).collect::<Vec<Error>>();
+
+        let tokens = Lexer::new(source).make_tokens().map_err(|e| in_module(vec![e]))?;
+        let ast = Parser::new(tokens).parse().map_err(in_module)?;
+        resolver::resolve(&ast).map_err(in_module)?;
+        checker::check(&ast).map_err(in_module)?;
+
+        check_imports_of(&ast, &source_file.name, checked)?;
+    }
+
+    Ok(())
+}
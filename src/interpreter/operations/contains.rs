@@ -0,0 +1,32 @@
+use crate::errors::{Error, ErrorType};
+
+use super::super::iterators::next;
+use super::super::symbol_table::SymbolTable;
+use super::super::symbols::{Symbol, SymbolType};
+
+impl Symbol {
+    /// Whether this value is an element of a list, tuple or iterator,
+    /// or a substring of a string. Iterators are consumed up to the element.
+    pub fn is_in(&self, other: &Symbol, symbol_table: &SymbolTable) -> Result<bool, Error> {
+        if let (SymbolType::String(a), SymbolType::String(b)) = (&self.value, &other.value) {
+            return Ok(b.contains(a.as_str()));
+        }
+
+        let iterator = match other.iterate() {
+            Some(iterator) => iterator,
+            None => return Err(Error::new_runtime(
+                ErrorType::TypeError,
+                format!("Cannot check for {} in {}", self.value.type_name(), other.value.type_name()),
+                &self.position
+            )),
+        };
+
+        while let Some(element) = next(&iterator, symbol_table)? {
+            if self.equals(&element, "in")? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
\ No newline at end of file
@@ -2,17 +2,23 @@ use crate::errors::{Error, ErrorType};
 
 #[allow(unused_imports)]
 use super::super::symbols::{Symbol, SymbolType};
+use super::checked_integer;
 
 impl Symbol {
     pub fn div(&self, other: &Symbol) -> Result<Symbol, Error> {
         match (self.value.clone(), other.value.clone()) {
-            (SymbolType::Integer(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(a / b), self.position)),
+            (SymbolType::Integer(_), SymbolType::Integer(0)) => Err(Error::new_runtime(
+                ErrorType::InvalidOperation,
+                "Division by zero".to_string(),
+                &self.position
+            )),
+            (SymbolType::Integer(a), SymbolType::Integer(b)) => checked_integer(a.checked_div(b), &self.position),
             (SymbolType::Float(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Float(a / b), self.position)),
             (SymbolType::Integer(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Float(a as f32 / b), self.position)),
             (SymbolType::Float(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Float(a / b as f32), self.position)),
             _ => Err(Error::new_runtime(
                 ErrorType::TypeError, 
-                format!("Cannot divide {:?} and {:?}", self.value, other.value),
+                format!("Cannot divide {} and {}", self.value.type_name(), other.value.type_name()),
                 &self.position
             ))
         }
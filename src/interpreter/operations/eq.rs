@@ -6,17 +6,46 @@ use super::super::symbols::{Symbol, SymbolType};
 
 impl Symbol {
     pub fn eq(&self, other: &Symbol) -> Result<Symbol, Error> {
-        match (self.value.clone(), other.value.clone()) {
-            (SymbolType::Integer(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a == b)), self.position)),
-            (SymbolType::Float(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a == b)), self.position)),
-            (SymbolType::Integer(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int((a as f32) == b)), self.position)),
-            (SymbolType::Float(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a == b as f32)), self.position)),
-            (SymbolType::String(a), SymbolType::String(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a == b)), self.position)),
+        let equal = self.equals(other, "==")?;
+        Ok(Symbol::new(SymbolType::Integer(bool_to_int(equal)), self.position))
+    }
+
+    /// Whether two values are equal. Lists, tuples, options and results
+    /// are equal if they hold equal values, so are compared element by element.
+    /// `operator` is the comparison being made, for the error if the values
+    /// cannot be compared.
+    pub fn equals(&self, other: &Symbol, operator: &str) -> Result<bool, Error> {
+        match (&self.value, &other.value) {
+            (SymbolType::Integer(a), SymbolType::Integer(b)) => Ok(a == b),
+            (SymbolType::Float(a), SymbolType::Float(b)) => Ok(a == b),
+            (SymbolType::Integer(a), SymbolType::Float(b)) => Ok((*a as f32) == *b),
+            (SymbolType::Float(a), SymbolType::Integer(b)) => Ok(*a == *b as f32),
+            (SymbolType::String(a), SymbolType::String(b)) => Ok(a == b),
+            (SymbolType::List(a), SymbolType::List(b)) |
+            (SymbolType::Tuple(a), SymbolType::Tuple(b)) => {
+                if a.len() != b.len() {
+                    return Ok(false);
+                }
+
+                for (a, b) in a.iter().zip(b) {
+                    if !a.equals(b, operator)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            },
+            (SymbolType::Some(a), SymbolType::Some(b)) |
+            (SymbolType::Ok(a), SymbolType::Ok(b)) |
+            (SymbolType::Err(a), SymbolType::Err(b)) => a.equals(b, operator),
+            (SymbolType::None, SymbolType::None) => Ok(true),
+            (SymbolType::Some(_) | SymbolType::None, SymbolType::Some(_) | SymbolType::None) |
+            (SymbolType::Ok(_) | SymbolType::Err(_), SymbolType::Ok(_) | SymbolType::Err(_)) => Ok(false),
             _ => Err(Error::new_runtime(
                 ErrorType::TypeError, 
-                format!("Cannot equal {:?} and {:?}", self.value, other.value),
+                format!("Cannot compare {} and {} with {}", self.value.type_name(), other.value.type_name(), operator),
                 &self.position
             ))
         }
     }
-}
\ No newline at end of file
+}
@@ -17,11 +17,59 @@ pub mod eq; // Equal to
 pub mod ne; // Not equal to
 pub mod and; // And
 pub mod or; // Or
+pub mod contains; // In
 
 // Unary operations
 pub mod not; // Not
 pub mod neg; // Negation
 
+use crate::errors::{Error, ErrorType};
+use crate::lexer::tokens::{Keyword, Token, TokenPosition, TokenType};
+use super::symbols::{Symbol, SymbolType};
+
+impl Symbol {
+    /// Apply the binary operation of an operator token, such as `+` or `+=`,
+    /// with errors reported at the operator
+    pub fn binary_operation(&self, operator: &Token, other: &Symbol) -> Result<Symbol, Error> {
+        let result = match operator.value {
+            TokenType::Plus | TokenType::PlusEqual => self.add(other),
+            TokenType::Minus | TokenType::MinusEqual => self.sub(other),
+            TokenType::Star | TokenType::StarEqual => self.mul(other),
+            TokenType::Slash | TokenType::SlashEqual => self.div(other),
+            TokenType::Caret => self.power(other),
+            TokenType::Less => self.lt(other),
+            TokenType::LessEqual => self.le(other),
+            TokenType::Greater => self.gt(other),
+            TokenType::GreaterEqual => self.ge(other),
+            TokenType::EqualEqual => self.eq(other),
+            TokenType::BangEqual => self.ne(other),
+            TokenType::Keyword(Keyword::And) => self.and(other),
+            TokenType::Keyword(Keyword::Or) => self.or(other),
+            _ => Err(Error::new_runtime(
+                ErrorType::InvalidOperation,
+                format!("Invalid operation: {}", operator),
+                &operator.position,
+            )),
+        };
+
+        result.map_err(|mut e| {
+            e.position = operator.position;
+            e
+        })
+    }
+}
+
+/// Result of integer arithmetic, which is `None` if it overflowed
+pub fn checked_integer(result: Option<i32>, position: &TokenPosition) -> Result<Symbol, Error> {
+    match result {
+        Some(value) => Ok(Symbol::new(SymbolType::Integer(value), *position)),
+        None => Err(Error::new_runtime(
+            ErrorType::InvalidOperation,
+            "Integer overflow".to_string(),
+            position
+        )),
+    }
+}
 
 pub fn bool_to_int(b: bool) -> i32 {
     if b {
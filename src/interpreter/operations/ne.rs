@@ -1,4 +1,4 @@
-use crate::errors::{Error, ErrorType};
+use crate::errors::Error;
 
 #[allow(unused_imports)]
 use super::bool_to_int;
@@ -6,17 +6,7 @@ use super::super::symbols::{Symbol, SymbolType};
 
 impl Symbol {
     pub fn ne(&self, other: &Symbol) -> Result<Symbol, Error> {
-        match (self.value.clone(), other.value.clone()) {
-            (SymbolType::Integer(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a != b)), self.position)),
-            (SymbolType::Float(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a != b)), self.position)),
-            (SymbolType::Integer(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int((a as f32) != b)), self.position)),
-            (SymbolType::Float(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a != b as f32)), self.position)),
-            (SymbolType::String(a), SymbolType::String(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a != b)), self.position)),
-            _ => Err(Error::new_runtime(
-                ErrorType::TypeError, 
-                format!("Cannot not equal {:?} and {:?}", self.value, other.value),
-                &self.position
-            ))
-        }
+        let equal = self.equals(other, "!=")?;
+        Ok(Symbol::new(SymbolType::Integer(bool_to_int(!equal)), self.position))
     }
-}
\ No newline at end of file
+}
@@ -2,15 +2,16 @@ use crate::errors::{Error, ErrorType};
 
 #[allow(unused_imports)]
 use super::super::symbols::{Symbol, SymbolType};
+use super::checked_integer;
 
 impl Symbol {
     pub fn neg(&self) -> Result<Symbol, Error> {
         match self.value {
-            SymbolType::Integer(a) => Ok(Symbol::new(SymbolType::Integer(-a), self.position)),
+            SymbolType::Integer(a) => checked_integer(a.checked_neg(), &self.position),
             SymbolType::Float(a) => Ok(Symbol::new(SymbolType::Float(-a), self.position)),
             _ => Err(Error::new_runtime(
                 ErrorType::TypeError, 
-                format!("Cannot apply neg to {:?}", self.value),
+                format!("Cannot negate {}", self.value.type_name()),
                 &self.position
             ))
         }
@@ -10,7 +10,7 @@ impl Symbol {
             SymbolType::Integer(a) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a == 0)), self.position)),
             _ => Err(Error::new_runtime(
                 ErrorType::TypeError, 
-                format!("Cannot apply not to {:?}", self.value),
+                format!("Cannot apply not to {}", self.value.type_name()),
                 &self.position
             ))
         }
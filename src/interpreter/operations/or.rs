@@ -10,7 +10,7 @@ impl Symbol {
             (SymbolType::Integer(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(bool_to_int(a == 1 || b == 1)), self.position)),
             _ => Err(Error::new_runtime(
                 ErrorType::TypeError, 
-                format!("Cannot apply or to {:?} and {:?}", self.value, other.value),
+                format!("Cannot apply or to {} and {}", self.value.type_name(), other.value.type_name()),
                 &self.position
             ))
         }
@@ -2,17 +2,25 @@ use crate::errors::{Error, ErrorType};
 
 #[allow(unused_imports)]
 use super::super::symbols::{Symbol, SymbolType};
+use super::checked_integer;
 
 impl Symbol {
     pub fn power(&self, other: &Symbol) -> Result<Symbol, Error> {
         match (self.value.clone(), other.value.clone()) {
-            (SymbolType::Integer(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Integer(i32::pow(a, b as u32)), self.position)),
+            (SymbolType::Integer(a), SymbolType::Integer(b)) => match u32::try_from(b) {
+                Ok(b) => checked_integer(a.checked_pow(b), &self.position),
+                Err(_) => Err(Error::new_runtime(
+                    ErrorType::InvalidOperation,
+                    format!("Cannot raise an integer to the negative power {}", b),
+                    &self.position
+                )),
+            },
             (SymbolType::Float(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Float(f32::powf(a, b)), self.position)),
             (SymbolType::Integer(a), SymbolType::Float(b)) => Ok(Symbol::new(SymbolType::Float(f32::powf(a as f32, b)), self.position)),
             (SymbolType::Float(a), SymbolType::Integer(b)) => Ok(Symbol::new(SymbolType::Float(f32::powf(a , b as f32)), self.position)),
             _ => Err(Error::new_runtime(
                 ErrorType::TypeError, 
-                format!("Cannot power {:?} and {:?}", self.value, other.value),
+                format!("Cannot raise {} to the power of {}", self.value.type_name(), other.value.type_name()),
                 &self.position
             ))
         }
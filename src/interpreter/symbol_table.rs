@@ -3,31 +3,59 @@
 //! The interpreter structure will store a symbol table with no parent,
 //! acting as the global symbol table.
 
-use std::collections::HashMap;
-use super::{symbols::{Symbol, SymbolType, FunctionSymbol}, builtin};
-use crate::{errors::Error, parser::nodes::{Node, ExecuteBuiltinNode}, lexer::tokens::{TokenPosition}};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use super::{symbols::{Symbol, SymbolType, FunctionSymbol, Parameter, ParameterType}, builtin::{self, Signature}, modules::ModuleLoader, DEFAULT_MAX_CALL_DEPTH};
+use crate::{errors::{self, Error, ErrorType}, parser::nodes::{Node, ExecuteBuiltinNode, execute_builtin_mod::BuiltinFunction}, lexer::tokens::{Keyword, TokenPosition}};
 
 #[derive(Clone, Debug)]
 pub struct SymbolTable<'a> {
     pub parent: Option<Box<&'a SymbolTable<'a>>>,
     pub symbols: HashMap<String, Symbol>,
+    /// Names which cannot be reassigned or shadowed, with where they were declared
+    pub constants: HashMap<String, TokenPosition>,
+    /// Module loader shared by every symbol table of the interpreter
+    pub modules: Rc<RefCell<ModuleLoader>>,
+    /// Number of function calls being made within each other, up to this scope
+    pub call_depth: usize,
+    /// Most function calls which may be made within each other
+    pub max_call_depth: usize,
 }
 
 /// General SymbolTable implementations =============================================================
 impl<'a> SymbolTable<'a> {
     /// Create a new symbol table
     pub fn new(parent: Box<&'a SymbolTable>) -> SymbolTable<'a> {
+        SymbolTable::with_symbols(parent, HashMap::new())
+    }
+
+    /// Create a new symbol table which already contains symbols
+    pub fn with_symbols(parent: Box<&'a SymbolTable>, symbols: HashMap<String, Symbol>) -> SymbolTable<'a> {
+        let modules = parent.modules.clone();
+        let (call_depth, max_call_depth) = (parent.call_depth, parent.max_call_depth);
         SymbolTable {
             parent: Some(parent),
-            symbols: HashMap::new(),
+            symbols,
+            constants: HashMap::new(),
+            modules,
+            call_depth,
+            max_call_depth,
         }
     }
 
     /// Nicer shortcut for creating a global symbol table
     pub fn new_global() -> SymbolTable<'a> {
+        SymbolTable::new_global_with_modules(Rc::new(RefCell::new(ModuleLoader::new())))
+    }
+
+    /// Create a global symbol table sharing an existing module loader
+    pub fn new_global_with_modules(modules: Rc<RefCell<ModuleLoader>>) -> SymbolTable<'a> {
         let mut symbol_table = SymbolTable {
             parent: None,
             symbols: HashMap::new(),
+            constants: HashMap::new(),
+            modules,
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
         };
 
         symbol_table.add_builtin_functions();
@@ -35,10 +63,84 @@ impl<'a> SymbolTable<'a> {
         symbol_table
     }
 
+    /// Count a function call made within this symbol table. Calls are refused
+    /// once `max_call_depth` of them are being made within each other, as
+    /// each one uses more of the interpreter's stack, or sooner if the stack
+    /// is nearly full.
+    pub fn enter_call(&mut self, position: &TokenPosition) -> Result<(), Error> {
+        if self.call_depth >= self.max_call_depth {
+            return Err(Error::new_runtime(
+                ErrorType::RecursionError,
+                format!("Maximum call depth of {} exceeded", self.max_call_depth),
+                position,
+            ).with_note("functions may be calling each other without end".to_string()));
+        }
+
+        if super::stack_is_nearly_full() {
+            return Err(Error::new_runtime(
+                ErrorType::RecursionError,
+                format!("Stack is full after {} calls", self.call_depth),
+                position,
+            ).with_note("functions may be calling each other without end, or calls may be nested in very deep expressions".to_string()));
+        }
+
+        self.call_depth += 1;
+        Ok(())
+    }
+
     /// Insert a new identifier into the symbol table
-    /// If the identifier already exists, it will be overwritten
-    pub fn set(&mut self, name: String, symbol: Symbol) {
+    /// If the identifier already exists, it will be overwritten,
+    /// unless it is a constant in this or any parent symbol table
+    pub fn set(&mut self, name: String, symbol: Symbol, position: &TokenPosition) -> Result<(), Error> {
+        self.check_not_constant(&name, position)?;
         self.symbols.insert(name, symbol);
+        Ok(())
+    }
+
+    /// Insert a new constant into the symbol table
+    pub fn set_constant(&mut self, name: String, symbol: Symbol, position: &TokenPosition) -> Result<(), Error> {
+        // running the same declaration again (e.g. in a loop) is not a reassignment
+        if self.constants.get(&name) != Some(position) {
+            self.check_not_constant(&name, position)?;
+        }
+
+        self.constants.insert(name.clone(), *position);
+        self.symbols.insert(name, symbol);
+        Ok(())
+    }
+
+    /// Get where a constant was declared, if the identifier is a constant.
+    /// Built-ins are constants declared at an internal position.
+    pub fn get_constant(&self, identifier: &String) -> Option<TokenPosition> {
+        match self.constants.get(identifier) {
+            Some(position) => Some(*position),
+            None => match &self.parent {
+                Some(parent) => parent.get_constant(identifier),
+                None => None,
+            },
+        }
+    }
+
+    /// Return an error if an identifier cannot be assigned to
+    fn check_not_constant(&self, name: &String, position: &TokenPosition) -> Result<(), Error> {
+        let declared = match self.get_constant(name) {
+            Some(declared) => declared,
+            None => return Ok(()),
+        };
+
+        // built-ins can only be shadowed within functions, not overwritten
+        let message = if declared.is_internal() {
+            if !self.constants.contains_key(name) {
+                return Ok(());
+            }
+            format!("Cannot overwrite built-in '{}'", name)
+        } else if self.constants.contains_key(name) {
+            format!("Cannot reassign constant '{}' declared at line {}, column {}", name, declared.line, declared.column)
+        } else {
+            format!("Cannot shadow constant '{}' declared at line {}, column {}", name, declared.line, declared.column)
+        };
+
+        Err(Error::new_runtime(ErrorType::ConstantError, message, position))
     }
 
     /// Get the value associated with an identifier
@@ -55,11 +157,127 @@ impl<'a> SymbolTable<'a> {
         }
     }
 
-    /// For function calls, set arguments to the function symbol table
-    pub fn set_args(&mut self, func_args: &Vec<String>, args: Vec<Symbol>) {
-        for i in func_args.iter().zip(args.iter()) {
-            self.set(i.0.clone(), i.1.clone());
+    /// Names defined in this symbol table and every parent of it
+    pub fn names(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.symbols.keys().collect();
+
+        if let Some(ref parent) = self.parent {
+            names.extend(parent.names());
+        }
+
+        names
+    }
+
+    /// Error for a name which is not defined, suggesting
+    /// defined names and keywords which are close to it
+    pub fn undefined(&self, name: &str, message: String, position: &TokenPosition) -> Error {
+        let names = self.names();
+
+        Error::new_runtime(ErrorType::UndefinedVariable, message, position)
+            .with_suggestions(name, names.into_iter().map(String::as_str).chain(Keyword::NAMES))
+    }
+
+    /// For function calls, set arguments to the function symbol table.
+    /// Positional arguments are bound in order, with any extra ones collected
+    /// by a variadic parameter. Keyword arguments are bound by name, and
+    /// parameters left over take their default value.
+    /// Returns the value of every parameter in order.
+    pub fn set_args(&mut self, parameters: &[Parameter], args: Vec<Symbol>, kwargs: Vec<(String, Symbol)>, position: &TokenPosition) -> Result<Vec<Symbol>, Error> {
+        let mut bound: Vec<Option<Symbol>> = vec![None; parameters.len()];
+        let fixed_count = parameters.iter().filter(|x| !x.variadic).count();
+        let variadic_index = parameters.iter().position(|x| x.variadic);
+
+        // positional arguments
+        let arg_count = args.len();
+        let mut extra_args: Vec<Symbol> = Vec::new();
+        let mut fixed_parameters = parameters.iter().enumerate().filter(|(_, x)| !x.variadic);
+
+        for arg in args {
+            match fixed_parameters.next() {
+                Some((index, _)) => bound[index] = Some(arg),
+                None => extra_args.push(arg),
+            }
+        }
+
+        match variadic_index {
+            Some(index) => bound[index] = Some(Symbol::new(SymbolType::List(extra_args), *position)),
+            None => if !extra_args.is_empty() {
+                return Err(Error::new_runtime(
+                    ErrorType::ArgumentError,
+                    format!("Expected at most {}, got {}", errors::count(fixed_count, "argument"), arg_count),
+                    position
+                ));
+            },
         }
+
+        // keyword arguments
+        for (name, value) in kwargs {
+            let index = match parameters.iter().position(|x| x.name == name && !x.variadic) {
+                Some(index) => index,
+                None => return Err(Error::new_runtime(
+                    ErrorType::ArgumentError,
+                    format!("Unexpected keyword argument '{}'", name),
+                    position
+                )),
+            };
+
+            if bound[index].is_some() {
+                return Err(Error::new_runtime(
+                    ErrorType::ArgumentError,
+                    format!("Got multiple values for argument '{}'", name),
+                    position
+                ));
+            }
+
+            bound[index] = Some(value);
+        }
+
+        // defaults and missing arguments
+        let mut values: Vec<Symbol> = Vec::new();
+
+        for (parameter, value) in parameters.iter().zip(bound) {
+            let value = match value.or_else(|| parameter.default.clone()) {
+                Some(value) => value,
+                None => return Err(Error::new_runtime(
+                    ErrorType::ArgumentError,
+                    format!("Missing argument '{}'", parameter.name),
+                    position
+                )),
+            };
+
+            let value = check_parameter_type(parameter, value, position)?;
+
+            match parameter.pattern {
+                Some(ref pattern) => pattern.bind(value.clone(), self)?,
+                None => self.set(parameter.name.clone(), value.clone(), position)?,
+            }
+            values.push(value);
+        }
+
+        Ok(values)
+    }
+}
+
+
+/// Ensure an argument has the type its parameter expects.
+/// Every value collected by a variadic parameter is checked.
+/// Gives the argument converted to the expected type.
+fn check_parameter_type(parameter: &Parameter, value: Symbol, position: &TokenPosition) -> Result<Symbol, Error> {
+    let check = |value: Symbol| match parameter.expected.accepts(&value) {
+        true => Ok(parameter.expected.convert(value)),
+        false => Err(Error::new_runtime(
+            ErrorType::TypeError,
+            format!("Expected argument '{}' of type {}, found {}", parameter.name, parameter.expected, value.value.type_name()),
+            position
+        )),
+    };
+
+    match (parameter.variadic, value.value) {
+        (true, SymbolType::List(values)) => {
+            let values = values.into_iter().map(check).collect::<Result<Vec<Symbol>, Error>>()?;
+            Ok(Symbol::new(SymbolType::List(values), value.position))
+        },
+        (_, other) => check(Symbol::new(other, value.position)),
     }
 }
 
@@ -67,13 +285,14 @@ impl<'a> SymbolTable<'a> {
 /// Built in functions =============================================================
 impl<'a> SymbolTable<'a> {
     /// Add a function to the symbol table
-    pub fn add_function(&mut self, name: &str, args: Vec<String>, func: &'static dyn Fn(&mut SymbolTable, &Vec<Node>) -> Result<Symbol, Error>) {
+    pub fn add_function(&mut self, name: &str, signature: Signature, func: BuiltinFunction) {
+        self.constants.insert(name.to_string(), TokenPosition::internal());
         self.symbols.insert(
             name.to_string(),
             Symbol::new(
                 SymbolType::Function(FunctionSymbol::new(
                     name.to_string(),
-                    args.clone(),
+                    signature.parameters,
                     Node::ExecuteBuiltinNode(Box::new(ExecuteBuiltinNode::new(func, None)))
                 )),
                 TokenPosition::internal()
@@ -86,16 +305,156 @@ impl<'a> SymbolTable<'a> {
         // print function
         self.add_function(
             "print",
-            vec!["text".to_string(), "arguments".to_string()],
+            Signature::new()
+                .required("text", ParameterType::String)
+                .variadic("arguments", ParameterType::Any),
             &builtin::print::print_func
         );
 
         // input function
         self.add_function(
             "input",
-            vec!["prefix".to_string()],
+            Signature::new()
+                .optional("prefix", SymbolType::String(String::new()), ParameterType::String),
             &builtin::input::input_func
         );
+
+        // exit function
+        self.add_function(
+            "exit",
+            Signature::new()
+                .optional("code", SymbolType::Integer(0), ParameterType::Integer),
+            &builtin::exit::exit_func
+        );
+
+        // result and option constructors
+        self.add_function(
+            "Ok",
+            Signature::new().required("value", ParameterType::Any),
+            &builtin::result::ok_func
+        );
+
+        self.add_function(
+            "Err",
+            Signature::new().required("error", ParameterType::Any),
+            &builtin::result::err_func
+        );
+
+        self.add_function(
+            "Some",
+            Signature::new().required("value", ParameterType::Any),
+            &builtin::result::some_func
+        );
+
+        // higher-order functions over iterables
+        self.add_function(
+            "map",
+            Signature::new()
+                .required("iterable", ParameterType::Iterable)
+                .required("function", ParameterType::Function),
+            &builtin::functional::map_func
+        );
+
+        self.add_function(
+            "filter",
+            Signature::new()
+                .required("iterable", ParameterType::Iterable)
+                .required("function", ParameterType::Function),
+            &builtin::functional::filter_func
+        );
+
+        self.add_function(
+            "reduce",
+            Signature::new()
+                .required("iterable", ParameterType::Iterable)
+                .required("function", ParameterType::Function)
+                .optional("initial", SymbolType::None, ParameterType::Any),
+            &builtin::functional::reduce_func
+        );
+
+        self.add_function(
+            "any",
+            Signature::new().required("iterable", ParameterType::Iterable),
+            &builtin::functional::any_func
+        );
+
+        self.add_function(
+            "all",
+            Signature::new().required("iterable", ParameterType::Iterable),
+            &builtin::functional::all_func
+        );
+
+        self.add_function(
+            "zip",
+            Signature::new().variadic("iterables", ParameterType::Iterable),
+            &builtin::functional::zip_func
+        );
+
+        self.add_function(
+            "enumerate",
+            Signature::new().required("iterable", ParameterType::Iterable),
+            &builtin::functional::enumerate_func
+        );
+
+        self.add_function(
+            "sorted",
+            Signature::new()
+                .required("iterable", ParameterType::Iterable)
+                .optional("key", SymbolType::None, ParameterType::Any),
+            &builtin::functional::sorted_func
+        );
+
+        // lazy iterators
+        self.add_function(
+            "range",
+            Signature::new()
+                .required("start", ParameterType::Integer)
+                .optional("stop", SymbolType::None, ParameterType::Any)
+                .optional("step", SymbolType::Integer(1), ParameterType::Integer),
+            &builtin::iterators::range_func
+        );
+
+        self.add_function(
+            "take",
+            Signature::new()
+                .required("iterable", ParameterType::Iterable)
+                .required("count", ParameterType::Integer),
+            &builtin::iterators::take_func
+        );
+
+        self.add_function(
+            "chain",
+            Signature::new().variadic("iterables", ParameterType::Iterable),
+            &builtin::iterators::chain_func
+        );
+
+        // number conversions
+        self.add_function(
+            "int",
+            Signature::new()
+                .required("value", ParameterType::Any)
+                .optional("base", SymbolType::Integer(10), ParameterType::Integer),
+            &builtin::numbers::int_func
+        );
+
+        self.add_function(
+            "float",
+            Signature::new().required("value", ParameterType::Any),
+            &builtin::numbers::float_func
+        );
+
+        // arguments given to the program, set by the interpreter running it
+        self.constants.insert("args".to_string(), TokenPosition::internal());
+        self.symbols.insert(
+            "args".to_string(),
+            Symbol::new(SymbolType::List(Vec::new()), TokenPosition::internal())
+        );
+
+        self.constants.insert("None".to_string(), TokenPosition::internal());
+        self.symbols.insert(
+            "None".to_string(),
+            Symbol::new(SymbolType::None, TokenPosition::internal())
+        );
     }
     
         
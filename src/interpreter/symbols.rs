@@ -1,4 +1,7 @@
-use crate::{parser::nodes::Node, lexer::tokens::TokenPosition};
+use std::{collections::HashMap, rc::Rc};
+
+use super::iterators::SharedIterator;
+use crate::{parser::nodes::{Node, Pattern}, lexer::tokens::TokenPosition, errors::SourceFile};
 
 /// All the symbols that can be stored in the symbol table
 #[derive(Debug, Clone)]
@@ -13,15 +16,71 @@ pub enum SymbolType {
     Float(f32),
     String(String),
     Node(Node),
+    List(Vec<Symbol>),
+    Tuple(Vec<Symbol>),
     Function(FunctionSymbol),
+    Module(Rc<ModuleSymbol>),
+    Ok(Box<Symbol>),
+    Err(Box<Symbol>),
+    Some(Box<Symbol>),
     None,
+    Iterator(SharedIterator),
 }
 
 #[derive(Debug, Clone)]
 pub struct FunctionSymbol {
     pub name: String,
-    pub args: Vec<String>,
+    pub parameters: Vec<Parameter>,
     pub node: Node,
+    /// Module the function was imported from, if any
+    pub module: Option<Rc<ModuleSymbol>>,
+    /// Calling the function returns an iterator over the values it yields
+    pub generator: bool,
+    /// Type of value the function is declared to return
+    pub returns: ParameterType,
+}
+
+/// A parameter of a function
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    /// Value used when no argument is given
+    pub default: Option<Symbol>,
+    /// Collects all remaining positional arguments into a list
+    pub variadic: bool,
+    /// Type of value the parameter accepts
+    pub expected: ParameterType,
+    /// Pattern the argument is destructured into, instead of binding the name
+    pub pattern: Option<Pattern>,
+}
+
+/// Types of values a parameter can expect, which are also
+/// the types named by annotations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterType {
+    Any,
+    Integer,
+    Float,
+    Number,
+    String,
+    List,
+    Tuple,
+    /// List, tuple or iterator
+    Iterable,
+    Iterator,
+    Function,
+    Result,
+    Option,
+    Module,
+    None,
+}
+
+/// An imported module and the names it defines
+#[derive(Debug, Clone)]
+pub struct ModuleSymbol {
+    pub name: String,
+    pub file: Rc<SourceFile>,
+    pub symbols: HashMap<String, Symbol>,
 }
 
 impl Symbol {
@@ -34,25 +93,148 @@ impl Symbol {
 }
 
 impl FunctionSymbol {
-    pub fn new(name: String, args: Vec<String>, node: Node) -> FunctionSymbol {
+    pub fn new(name: String, parameters: Vec<Parameter>, node: Node) -> FunctionSymbol {
         FunctionSymbol {
             name,
-            args,
+            parameters,
             node,
+            module: None,
+            generator: false,
+            returns: ParameterType::Any,
+        }
+    }
+
+    /// Make the function a generator
+    pub fn generator(mut self, generator: bool) -> FunctionSymbol {
+        self.generator = generator;
+        self
+    }
+
+    /// Ensure the function returns a type of value
+    pub fn returns(mut self, returns: ParameterType) -> FunctionSymbol {
+        self.returns = returns;
+        self
+    }
+}
+
+impl Parameter {
+    pub fn new(name: String, default: Option<Symbol>, variadic: bool) -> Parameter {
+        Parameter {
+            name,
+            default,
+            variadic,
+            expected: ParameterType::Any,
+            pattern: None,
+        }
+    }
+
+    /// Ensure the parameter accepts a value
+    pub fn expected(mut self, expected: ParameterType) -> Parameter {
+        self.expected = expected;
+        self
+    }
+
+    /// Destructure the argument into a pattern
+    pub fn destructure(mut self, pattern: Pattern) -> Parameter {
+        self.pattern = Some(pattern);
+        self
+    }
+}
+
+impl ParameterType {
+    /// Type named by an annotation, such as `int` in `w: int`
+    pub fn from_annotation(name: &str) -> Option<ParameterType> {
+        match name {
+            "any" => Some(ParameterType::Any),
+            "int" => Some(ParameterType::Integer),
+            "float" => Some(ParameterType::Float),
+            "string" => Some(ParameterType::String),
+            "list" => Some(ParameterType::List),
+            "tuple" => Some(ParameterType::Tuple),
+            "iterator" => Some(ParameterType::Iterator),
+            "function" => Some(ParameterType::Function),
+            "result" => Some(ParameterType::Result),
+            "option" => Some(ParameterType::Option),
+            "module" => Some(ParameterType::Module),
+            "none" => Some(ParameterType::None),
+            _ => None,
+        }
+    }
+
+    /// Whether a value can be passed to a parameter of this type.
+    /// Integers are accepted where floats are.
+    pub fn accepts(&self, symbol: &Symbol) -> bool {
+        match self {
+            ParameterType::Any => true,
+            ParameterType::Integer => matches!(symbol.value, SymbolType::Integer(_)),
+            ParameterType::Float | ParameterType::Number => matches!(symbol.value, SymbolType::Integer(_) | SymbolType::Float(_)),
+            ParameterType::String => matches!(symbol.value, SymbolType::String(_)),
+            ParameterType::List => matches!(symbol.value, SymbolType::List(_)),
+            ParameterType::Tuple => matches!(symbol.value, SymbolType::Tuple(_)),
+            ParameterType::Iterable => symbol.iterate().is_some(),
+            ParameterType::Iterator => matches!(symbol.value, SymbolType::Iterator(_)),
+            ParameterType::Function => matches!(symbol.value, SymbolType::Function(_)),
+            ParameterType::Result => matches!(symbol.value, SymbolType::Ok(_) | SymbolType::Err(_)),
+            ParameterType::Option => matches!(symbol.value, SymbolType::Some(_) | SymbolType::None),
+            ParameterType::Module => matches!(symbol.value, SymbolType::Module(_)),
+            ParameterType::None => matches!(symbol.value, SymbolType::None),
+        }
+    }
+
+    /// Give an accepted value the type of this parameter, turning
+    /// integers into floats where floats are expected
+    pub fn convert(&self, symbol: Symbol) -> Symbol {
+        match (self, symbol.value) {
+            (ParameterType::Float, SymbolType::Integer(value)) => Symbol::new(SymbolType::Float(value as f32), symbol.position),
+            (_, value) => Symbol::new(value, symbol.position),
         }
     }
 }
 
-impl ToString for Symbol {
-    fn to_string(&self) -> String {
-        match &self.value {
-            SymbolType::Integer(value) => value.to_string(),
-            SymbolType::Float(value) => value.to_string(),
-            SymbolType::String(value) => value.to_string(),
-            SymbolType::Node(value) => value.to_string(),
-            SymbolType::Function(value) => value.to_string(),
-            SymbolType::None => "None".to_string(),
+impl SymbolType {
+    /// Name of the type of value, for error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SymbolType::Integer(_) => "int",
+            SymbolType::Float(_) => "float",
+            SymbolType::String(_) => "string",
+            SymbolType::Node(_) => "node",
+            SymbolType::List(_) => "list",
+            SymbolType::Tuple(_) => "tuple",
+            SymbolType::Function(_) => "function",
+            SymbolType::Module(_) => "module",
+            SymbolType::Ok(_) | SymbolType::Err(_) => "result",
+            SymbolType::Some(_) | SymbolType::None => "option",
+            SymbolType::Iterator(_) => "iterator",
+        }
+    }
+}
+
+impl ModuleSymbol {
+    pub fn new(name: String, file: Rc<SourceFile>, symbols: HashMap<String, Symbol>) -> ModuleSymbol {
+        ModuleSymbol {
+            name,
+            file,
+            symbols,
+        }
+    }
+
+    /// Get a name defined by the module.
+    /// Functions remember the module so they can see its other names when called.
+    pub fn get(self: &Rc<Self>, name: &str) -> Option<Symbol> {
+        let mut symbol = self.symbols.get(name)?.clone();
+
+        if let SymbolType::Function(ref mut func) = symbol.value {
+            func.module = Some(self.clone());
         }
+
+        Some(symbol)
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
     }
 }
 
@@ -62,6 +244,35 @@ impl std::fmt::Display for FunctionSymbol {
     }
 }
 
+impl std::fmt::Display for ModuleSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ModuleSymbol: {}", self.name)
+    }
+}
+
+impl std::fmt::Display for ParameterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ParameterType::Any => "any",
+            ParameterType::Integer => "int",
+            ParameterType::Float => "float",
+            ParameterType::Number => "number",
+            ParameterType::String => "string",
+            ParameterType::List => "list",
+            ParameterType::Tuple => "tuple",
+            ParameterType::Iterable => "iterable",
+            ParameterType::Iterator => "iterator",
+            ParameterType::Function => "function",
+            ParameterType::Result => "result",
+            ParameterType::Option => "option",
+            ParameterType::Module => "module",
+            ParameterType::None => "none",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
 impl std::fmt::Display for SymbolType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -69,8 +280,24 @@ impl std::fmt::Display for SymbolType {
             SymbolType::Float(value) => write!(f, "{}", value),
             SymbolType::String(value) => write!(f, "{}", value),
             SymbolType::Node(value) => write!(f, "{}", value),
+            SymbolType::List(values) => {
+                let values: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+                write!(f, "[{}]", values.join(", "))
+            },
+            SymbolType::Tuple(values) => {
+                let values: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+                match values.len() {
+                    1 => write!(f, "({},)", values[0]),
+                    _ => write!(f, "({})", values.join(", ")),
+                }
+            },
             SymbolType::Function(value) => write!(f, "{}", value),
+            SymbolType::Module(value) => write!(f, "{}", value),
+            SymbolType::Ok(value) => write!(f, "Ok({})", value),
+            SymbolType::Err(value) => write!(f, "Err({})", value),
+            SymbolType::Some(value) => write!(f, "Some({})", value),
             SymbolType::None => write!(f, "None"),
+            SymbolType::Iterator(_) => write!(f, "<iterator>"),
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 //! Lexes and tokenizes a string into a stream of tokens.
 
 pub mod tokens;
+pub mod numbers;
 
 use tokens::{
     Token,
@@ -8,11 +9,16 @@ use tokens::{
     Keyword,
 };
 
+use std::rc::Rc;
+
 use crate::errors::{Error, ErrorType};
 
 use self::tokens::TokenPosition;
 
 const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+/// Message of the error for a string with no closing quote, which the
+/// REPL uses to tell that the string continues on the next line
+pub const UNTERMINATED_STRING: &str = "Unterminated string";
 const LETTERS: [char; 52] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z'];
 
 
@@ -29,19 +35,23 @@ pub struct LexerPosition {
     pub line: u32,
     pub column: u32,
     pub pos: usize,
+    /// Byte offset of the current character
+    pub offset: usize,
     pub current_char: Option<char>,
-    text: String,
+    /// Shared so that positions are cheap to copy for every token
+    text: Rc<str>,
 }
 
 impl LexerPosition {
-    pub fn new(text: &String) -> LexerPosition {
-        let current_char = text.chars().nth(0);
+    pub fn new(text: &str) -> LexerPosition {
+        let current_char = text.chars().next();
         LexerPosition {
             line: 1,
             column: 1,
             pos: 0,
+            offset: 0,
             current_char,
-            text: text.clone(),
+            text: Rc::from(text),
         }
     }
 
@@ -50,21 +60,28 @@ impl LexerPosition {
             line: token_pos.line,
             column: token_pos.column,
             pos: 0,
+            offset: token_pos.start as usize,
             current_char: None,
-            text: "".to_string(),
+            text: Rc::from(""),
         }
     }
 
+    /// Move past the current character. Moving past a newline
+    /// starts the next line, so the newline itself is at the end of its line.
     pub fn advance(&mut self) {
-        self.pos += 1;
-        self.current_char = self.text.chars().nth(self.pos);
+        if let Some(current_char) = self.current_char {
+            self.offset += current_char.len_utf8();
 
-        if self.current_char == Some('\n') {
-            self.line += 1;
-            self.column = 0;
-        } else {
-            self.column += 1;
+            if current_char == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
         }
+
+        self.pos += 1;
+        self.current_char = self.text[self.offset..].chars().next();
     }
 }
 
@@ -87,89 +104,103 @@ impl Lexer {
                 continue;
             }
 
+            let start = self.position.clone();
+
             // check for single-character tokens
             match current_char {
                 '(' => {
-                    tokens.push(Token::new(TokenType::LeftParen, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::LeftParen, &start, &self.position));
                     continue;
                 },
                 ')' => {
-                    tokens.push(Token::new(TokenType::RightParen, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::RightParen, &start, &self.position));
                     continue;
                 },
                 '{' => {
-                    tokens.push(Token::new(TokenType::LeftBrace, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::LeftBrace, &start, &self.position));
                     continue;
                 },
                 '}' => {
-                    tokens.push(Token::new(TokenType::RightBrace, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::RightBrace, &start, &self.position));
                     continue;
                 },
                 ',' => {
-                    tokens.push(Token::new(TokenType::Comma, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::Comma, &start, &self.position));
                     continue;
                 },
                 '.' => {
-                    tokens.push(Token::new(TokenType::Dot, &self.position));
+                    if self.peek_char().is_some_and(|x| self.is_digit(x)) {
+                        return Err(Error::new_lexer(
+                            ErrorType::SyntaxError,
+                            "Number cannot start with a decimal point, add a 0 before it".to_string(),
+                            &self.position
+                        ));
+                    }
+
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::Dot, &start, &self.position));
                     continue;
                 },
                 ';' => {
-                    tokens.push(Token::new(TokenType::Semicolon, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::Semicolon, &start, &self.position));
                     continue;
                 },
                 '+' => {
-                    if self.peek_char() == Some('=') {
+                    let token_type = if self.peek_char() == Some('=') {
                         // arithmetic assignment operator
-                        tokens.push(Token::new(TokenType::PlusEqual, &self.position));
-                        // advance twice
                         self.position.advance();
+                        TokenType::PlusEqual
                     } else {
-                        tokens.push(Token::new(TokenType::Plus, &self.position));
-                    }
+                        TokenType::Plus
+                    };
                     self.position.advance();
+                    tokens.push(Token::new(token_type, &start, &self.position));
                     continue;
                 },
                 '-' => {
-                    if self.peek_char() == Some('=') {
+                    let token_type = if self.peek_char() == Some('=') {
                         // arithmetic assignment operator
-                        tokens.push(Token::new(TokenType::MinusEqual, &self.position));
-                        // advance twice
                         self.position.advance();
+                        TokenType::MinusEqual
+                    } else if self.peek_char() == Some('>') {
+                        // return type arrow
+                        self.position.advance();
+                        TokenType::Arrow
                     } else {
-                        tokens.push(Token::new(TokenType::Minus, &self.position));
-                    }
+                        TokenType::Minus
+                    };
                     self.position.advance();
+                    tokens.push(Token::new(token_type, &start, &self.position));
                     continue;
                 },
                 '*' => {
-                    if self.peek_char() == Some('=') {
+                    let token_type = if self.peek_char() == Some('=') {
                         // arithmetic assignment operator
-                        tokens.push(Token::new(TokenType::StarEqual, &self.position));
-                        // advance twice
                         self.position.advance();
+                        TokenType::StarEqual
                     } else {
-                        tokens.push(Token::new(TokenType::Star, &self.position));
-                    }
+                        TokenType::Star
+                    };
                     self.position.advance();
+                    tokens.push(Token::new(token_type, &start, &self.position));
                     continue;
                 },
                 '/' => {
-                    if self.peek_char() == Some('=') {
+                    let token_type = if self.peek_char() == Some('=') {
                         // arithmetic assignment operator
-                        tokens.push(Token::new(TokenType::SlashEqual, &self.position));
-                        // advance twice
                         self.position.advance();
+                        TokenType::SlashEqual
                     } else {
-                        tokens.push(Token::new(TokenType::Slash, &self.position));
-                    }
+                        TokenType::Slash
+                    };
                     self.position.advance();
+                    tokens.push(Token::new(token_type, &start, &self.position));
                     continue;
                 },
                 '=' => {
@@ -177,6 +208,21 @@ impl Lexer {
                     tokens.push(token);
                     continue;
                 },
+                '|' => {
+                    // pipeline operator, `|` is not a token on its own
+                    if self.peek_char() != Some('>') {
+                        return Err(Error::new_lexer(
+                            ErrorType::InvalidToken,
+                            "Unexpected character: '|', did you mean '|>'?".to_string(),
+                            &self.position
+                        ));
+                    }
+
+                    self.position.advance();
+                    self.position.advance();
+                    tokens.push(Token::new(TokenType::Pipe, &start, &self.position));
+                    continue;
+                },
                 '!' => {
                     let token = self.make_not_equals()?;
                     tokens.push(token);
@@ -193,8 +239,8 @@ impl Lexer {
                     continue;
                 },
                 '^' => {
-                    tokens.push(Token::new(TokenType::Caret, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::Caret, &start, &self.position));
                     continue;
                 },
                 '"' => {
@@ -203,20 +249,30 @@ impl Lexer {
                     continue;
                 },
                 '[' => {
-                    tokens.push(Token::new(TokenType::LeftSquare, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::LeftSquare, &start, &self.position));
                     continue;
                 },
                 ']' => {
-                    tokens.push(Token::new(TokenType::RightSquare, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::RightSquare, &start, &self.position));
                     continue;
                 },
                 '_' => {
-                    tokens.push(Token::new(TokenType::Underscore, &self.position));
                     self.position.advance();
+                    tokens.push(Token::new(TokenType::Underscore, &start, &self.position));
                     continue;
-                }
+                },
+                '?' => {
+                    self.position.advance();
+                    tokens.push(Token::new(TokenType::Question, &start, &self.position));
+                    continue;
+                },
+                ':' => {
+                    self.position.advance();
+                    tokens.push(Token::new(TokenType::Colon, &start, &self.position));
+                    continue;
+                },
                 _ => (),
             };
             
@@ -242,7 +298,7 @@ impl Lexer {
             ));
         }
 
-        tokens.push(Token::new(TokenType::EOF, &self.position));
+        tokens.push(Token::new(TokenType::EOF, &self.position, &self.position));
 
         Ok(tokens)
     }
@@ -275,31 +331,37 @@ impl Lexer {
         }
     }
 
-    /// Try to make a string
+    /// Try to make a string, which may span several lines
     fn make_string(&mut self) -> Result<Token, Error> {
+        let start = self.position.clone();
         let mut string = String::new();
 
         self.expect('"')?;
         self.position.advance();
 
-        while self.get_current_char("Unterminated string")? != '"' {
-            string.push(self.get_current_char("Unterminated string")?);
-            self.position.advance();
-        };
+        while let Some(current_char) = self.position.current_char {
+            if current_char == '"' {
+                self.position.advance();
+                return Ok(Token::new(TokenType::String(string), &start, &self.position));
+            }
 
-        self.expect('"')?;
-        self.position.advance();
+            string.push(current_char);
+            self.position.advance();
+        }
 
-        Ok(Token::new(TokenType::String(string), &self.position))
+        // point at the opening quote, as the end of the file is not useful
+        Err(Error::new_lexer(ErrorType::InvalidToken, UNTERMINATED_STRING.to_string(), &start))
     }
 
     /// Peek at the next character in the lexer
     fn peek_char(&self) -> Option<char> {
-        self.text.chars().nth(self.position.pos + 1)
+        self.text[self.position.offset..].chars().nth(1)
     }
 
     /// Try to make >=, if not just return !
     fn make_less_than(&mut self) -> Result<Token, Error> {
+        let start = self.position.clone();
+
         let token_type = if self.peek_char() == Some('=') {
             self.position.advance();
             TokenType::LessEqual
@@ -307,15 +369,17 @@ impl Lexer {
             TokenType::Less
         };
 
-        let token = Token::new(token_type, &self.position);
-
         self.position.advance();
 
+        let token = Token::new(token_type, &start, &self.position);
+
         Ok(token)
     }
 
     /// Try to make >=, if not just return !
     fn make_greater_than(&mut self) -> Result<Token, Error> {
+        let start = self.position.clone();
+
         let token_type = if self.peek_char() == Some('=') {
             self.position.advance();
             TokenType::GreaterEqual
@@ -323,15 +387,17 @@ impl Lexer {
             TokenType::Greater
         };
 
-        let token = Token::new(token_type, &self.position);
-
         self.position.advance();
 
+        let token = Token::new(token_type, &start, &self.position);
+
         Ok(token)
     }
 
     /// Try to make !=, if not just return !
     fn make_not_equals(&mut self) -> Result<Token, Error> {
+        let start = self.position.clone();
+
         let token_type = if self.peek_char() == Some('=') {
             self.position.advance();
             TokenType::BangEqual
@@ -339,15 +405,17 @@ impl Lexer {
             TokenType::Bang
         };
 
-        let token = Token::new(token_type, &self.position);
-
         self.position.advance();
 
+        let token = Token::new(token_type, &start, &self.position);
+
         Ok(token)
     }
 
     /// Try to make an == operator, if not just return a single =
     fn make_equals(&mut self) -> Result<Token, Error> {
+        let start = self.position.clone();
+
         let token_type = if self.peek_char() == Some('=') {
             self.position.advance();
             TokenType::EqualEqual
@@ -355,15 +423,16 @@ impl Lexer {
             TokenType::Equal
         };
 
-        let token = Token::new(token_type, &self.position);
-
         self.position.advance();
 
+        let token = Token::new(token_type, &start, &self.position);
+
         Ok(token)
     }
 
     /// Create an identifier token from the current position
     fn make_identifier(&mut self) -> Result<Token, Error> {
+        let start = self.position.clone();
         let mut identifier: String = String::new();
 
         while let Some(current_char) = self.position.current_char {
@@ -402,41 +471,43 @@ impl Lexer {
         // determine whether it is a identifier or keyword
 
         match Keyword::from(&identifier) {
-            Some(keyword) => Ok(Token::new(TokenType::Keyword(keyword), &self.position)),
-            None => Ok(Token::new(TokenType::Identifier(identifier), &self.position)),
+            Some(keyword) => Ok(Token::new(TokenType::Keyword(keyword), &start, &self.position)),
+            None => Ok(Token::new(TokenType::Identifier(identifier), &start, &self.position)),
         }
 
     }
 
-    /// Create a number token from the current position
+    /// Create a number token from the current position.
+    /// The literal is read up to the first character which cannot be part of it,
+    /// so that a misplaced letter or separator is reported where it is.
     fn make_number(&mut self) -> Result<Token, Error> {
-        let mut number = String::new();
-        let mut dot_count = 0;
+        let start = self.position.clone();
+        let mut literal = String::new();
 
         while let Some(current_char) = self.position.current_char {
-            if self.is_digit(current_char) {
-                number.push(current_char);
-                self.position.advance();
-            } else if current_char == '.' {
-                if dot_count > 0 {
-                    return Err(Error::new_lexer(
-                        ErrorType::SyntaxError,
-                        "Number cannot have more than one decimal point".to_string(),
-                        &self.position
-                    ));
-                }
-                dot_count += 1;
-                number.push(current_char);
+            let prefixed = numbers::radix_of_prefix(&literal).is_some();
+            let exponent_sign = !prefixed && (current_char == '+' || current_char == '-') && literal.ends_with(['e', 'E']);
+
+            if current_char.is_ascii_alphanumeric() || current_char == '_' || (current_char == '.' && !prefixed) || exponent_sign {
+                literal.push(current_char);
                 self.position.advance();
             } else {
                 break;
             }
         }
 
-        if dot_count == 0 {
-            Ok(Token::new(TokenType::Integer(number.parse::<i32>().unwrap()), &self.position))
-        } else {
-            Ok(Token::new(TokenType::Float(number.parse::<f32>().unwrap()), &self.position))
+        match numbers::parse_number(&literal) {
+            Ok(numbers::Number::Integer(value)) => Ok(Token::new(TokenType::Integer(value), &start, &self.position)),
+            Ok(numbers::Number::Float(value)) => Ok(Token::new(TokenType::Float(value), &start, &self.position)),
+            Err(e) => {
+                let mut position = start.clone();
+                for _ in 0..e.offset {
+                    position.advance();
+                }
+
+                // the error covers the rest of the literal from where it was found
+                Err(Error::new_lexer_between(ErrorType::SyntaxError, e.message, &position, &self.position))
+            },
         }
     }
 
@@ -447,4 +518,164 @@ impl Lexer {
     fn is_letter(&self, c: char) -> bool {
         LETTERS.contains(&c)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kind, line, column and byte range of each token
+    fn spans(text: &str) -> Vec<(TokenType, u32, u32, u32, u32)> {
+        Lexer::new(text.to_string())
+            .make_tokens()
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.value, x.position.line, x.position.column, x.position.start, x.position.end))
+            .collect()
+    }
+
+    /// Span of the only token in the text, other than the end of file
+    fn span(text: &str) -> (TokenType, u32, u32, u32, u32) {
+        let tokens = spans(text);
+        assert_eq!(tokens.len(), 2, "expected one token in {:?}, got {:?}", text, tokens);
+        tokens[0].clone()
+    }
+
+    #[test]
+    fn single_character_tokens() {
+        let cases = [
+            ("(", TokenType::LeftParen),
+            (")", TokenType::RightParen),
+            ("{", TokenType::LeftBrace),
+            ("}", TokenType::RightBrace),
+            ("[", TokenType::LeftSquare),
+            ("]", TokenType::RightSquare),
+            (",", TokenType::Comma),
+            (".", TokenType::Dot),
+            ("-", TokenType::Minus),
+            ("+", TokenType::Plus),
+            (";", TokenType::Semicolon),
+            ("/", TokenType::Slash),
+            ("*", TokenType::Star),
+            ("^", TokenType::Caret),
+            ("_", TokenType::Underscore),
+            ("?", TokenType::Question),
+            (":", TokenType::Colon),
+            ("!", TokenType::Bang),
+            ("=", TokenType::Equal),
+            (">", TokenType::Greater),
+            ("<", TokenType::Less),
+        ];
+
+        for (text, token_type) in cases {
+            // after a space, so the token does not start at the beginning
+            assert_eq!(span(&format!(" {}", text)), (token_type, 1, 2, 1, 2), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn two_character_tokens() {
+        let cases = [
+            ("!=", TokenType::BangEqual),
+            ("==", TokenType::EqualEqual),
+            (">=", TokenType::GreaterEqual),
+            ("<=", TokenType::LessEqual),
+            ("+=", TokenType::PlusEqual),
+            ("-=", TokenType::MinusEqual),
+            ("*=", TokenType::StarEqual),
+            ("/=", TokenType::SlashEqual),
+            ("->", TokenType::Arrow),
+            ("|>", TokenType::Pipe),
+        ];
+
+        for (text, token_type) in cases {
+            assert_eq!(span(&format!(" {}", text)), (token_type, 1, 2, 1, 3), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(span(" 12345"), (TokenType::Integer(12345), 1, 2, 1, 6));
+        assert_eq!(span(" 0xff"), (TokenType::Integer(255), 1, 2, 1, 5));
+        assert_eq!(span(" 1_000"), (TokenType::Integer(1000), 1, 2, 1, 6));
+        assert_eq!(span(" 123.45"), (TokenType::Float(123.45), 1, 2, 1, 7));
+        assert_eq!(span(" 1e-3"), (TokenType::Float(0.001), 1, 2, 1, 5));
+        assert_eq!(span(" \"hello\""), (TokenType::String("hello".to_string()), 1, 2, 1, 8));
+        assert_eq!(span(" \"\""), (TokenType::String("".to_string()), 1, 2, 1, 3));
+    }
+
+    #[test]
+    fn identifiers_and_keywords() {
+        assert_eq!(span(" my_identifier"), (TokenType::Identifier("my_identifier".to_string()), 1, 2, 1, 14));
+        assert_eq!(span(" x1"), (TokenType::Identifier("x1".to_string()), 1, 2, 1, 3));
+        assert_eq!(span(" func"), (TokenType::Keyword(Keyword::Func), 1, 2, 1, 5));
+        assert_eq!(span(" yield"), (TokenType::Keyword(Keyword::Yield), 1, 2, 1, 6));
+    }
+
+    #[test]
+    fn end_of_file() {
+        assert_eq!(spans(""), vec![(TokenType::EOF, 1, 1, 0, 0)]);
+        assert_eq!(spans("a\n").last().unwrap(), &(TokenType::EOF, 2, 1, 2, 2));
+    }
+
+    #[test]
+    fn tokens_after_a_newline() {
+        let tokens = spans("let x = 1;\nx += 2;");
+
+        assert_eq!(tokens[0], (TokenType::Keyword(Keyword::Let), 1, 1, 0, 3));
+        assert_eq!(tokens[1], (TokenType::Identifier("x".to_string()), 1, 5, 4, 5));
+        assert_eq!(tokens[2], (TokenType::Equal, 1, 7, 6, 7));
+        assert_eq!(tokens[3], (TokenType::Integer(1), 1, 9, 8, 9));
+        assert_eq!(tokens[4], (TokenType::Semicolon, 1, 10, 9, 10));
+        // the first character of a line is in column 1
+        assert_eq!(tokens[5], (TokenType::Identifier("x".to_string()), 2, 1, 11, 12));
+        assert_eq!(tokens[6], (TokenType::PlusEqual, 2, 3, 13, 15));
+        assert_eq!(tokens[7], (TokenType::Integer(2), 2, 6, 16, 17));
+        assert_eq!(tokens[8], (TokenType::Semicolon, 2, 7, 17, 18));
+    }
+
+    #[test]
+    fn multi_line_strings() {
+        let tokens = spans("print(\"one\ntwo\");\nx");
+
+        // the string starts at its opening quote and ends after its closing one
+        assert_eq!(tokens[2], (TokenType::String("one\ntwo".to_string()), 1, 7, 6, 15));
+        // tokens after the string are on the lines it spans onto
+        assert_eq!(tokens[3], (TokenType::RightParen, 2, 5, 15, 16));
+        assert_eq!(tokens[5], (TokenType::Identifier("x".to_string()), 3, 1, 18, 19));
+    }
+
+    #[test]
+    fn multibyte_characters() {
+        let tokens = spans("\"héllo\" x");
+
+        // columns count characters, offsets count bytes
+        assert_eq!(tokens[0], (TokenType::String("héllo".to_string()), 1, 1, 0, 8));
+        assert_eq!(tokens[1], (TokenType::Identifier("x".to_string()), 1, 9, 9, 10));
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let error = Lexer::new("x = \"abc\ndef".to_string()).make_tokens().unwrap_err();
+
+        assert_eq!(error.error_message, "Unterminated string");
+        assert_eq!((error.position.line, error.position.column), (1, 5));
+        // the error covers the opening quote
+        assert_eq!((error.position.start, error.position.end), (4, 5));
+    }
+
+    /// Byte range of the error in the text
+    fn error_span(text: &str) -> (u32, u32) {
+        let error = Lexer::new(text.to_string()).make_tokens().unwrap_err();
+        (error.position.start, error.position.end)
+    }
+
+    #[test]
+    fn errors_cover_the_character_or_literal_which_caused_them() {
+        assert_eq!(error_span("a $ b"), (2, 3));
+        assert_eq!(error_span("a é b"), (2, 4));
+        assert_eq!(error_span("a | b"), (2, 3));
+        assert_eq!(error_span("x = 99999999999;"), (4, 15));
+        assert_eq!(error_span("x = 0b102;"), (8, 9));
+    }
+}
@@ -0,0 +1,167 @@
+//! Reads the digits of number literals. Shared by the lexer and the
+//! `int` and `float` built-ins, so text is parsed the same way in both.
+//!
+//! Integers may be written in hexadecimal (`0xFF`), binary (`0b1010`) or
+//! octal (`0o755`), floats may have an exponent (`1.5e-3`), and digits of
+//! either may be grouped with `_` separators (`1_000_000`).
+
+/// Value of a number literal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Integer(i32),
+    Float(f32),
+}
+
+/// Reason a number could not be read, and the index of the character
+/// within the text it was found at
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberError {
+    pub message: String,
+    pub offset: usize,
+}
+
+impl NumberError {
+    fn new(message: String, offset: usize) -> NumberError {
+        NumberError { message, offset }
+    }
+}
+
+/// Radix of the prefix a number starts with, such as 16 for `0x`
+pub fn radix_of_prefix(text: &str) -> Option<u32> {
+    match text.get(..2)? {
+        "0x" | "0X" => Some(16),
+        "0b" | "0B" => Some(2),
+        "0o" | "0O" => Some(8),
+        _ => None,
+    }
+}
+
+/// Name of numbers written in a radix
+pub fn radix_name(radix: u32) -> String {
+    match radix {
+        2 => "binary".to_string(),
+        8 => "octal".to_string(),
+        10 => "decimal".to_string(),
+        16 => "hexadecimal".to_string(),
+        _ => format!("base {}", radix),
+    }
+}
+
+/// Read a whole number literal, such as `0xFF`, `1_000` or `1.5e-3`
+pub fn parse_number(text: &str) -> Result<Number, NumberError> {
+    if let Some(radix) = radix_of_prefix(text) {
+        return parse_integer(&text[2..], radix).map(Number::Integer).map_err(|e| NumberError::new(e.message, e.offset + 2));
+    }
+
+    // split into the whole part, fraction and exponent
+    let exponent_start = text.find(['e', 'E']);
+    let mantissa = &text[..exponent_start.unwrap_or(text.len())];
+
+    if let Some(dot) = mantissa.find('.') {
+        if let Some(second) = mantissa[dot + 1..].find('.') {
+            return Err(NumberError::new("Number cannot have more than one decimal point".to_string(), dot + 1 + second));
+        }
+    }
+
+    if exponent_start.is_none() && !mantissa.contains('.') {
+        return parse_integer(text, 10).map(Number::Integer);
+    }
+
+    let mut digits = String::new();
+
+    let (whole, fraction) = match mantissa.find('.') {
+        Some(dot) => (&mantissa[..dot], Some((dot + 1, &mantissa[dot + 1..]))),
+        None => (mantissa, None),
+    };
+
+    if whole.is_empty() && fraction.is_none_or(|(_, fraction)| fraction.is_empty()) {
+        return Err(NumberError::new("Expected decimal digits".to_string(), 0));
+    }
+
+    digits.push_str(&strip_separators(whole, 10, 0)?);
+
+    if let Some((start, fraction)) = fraction {
+        digits.push('.');
+        // a trailing dot, as in `1.`, has no fraction digits
+        if !fraction.is_empty() {
+            digits.push_str(&strip_separators(fraction, 10, start)?);
+        }
+    }
+
+    if let Some(start) = exponent_start {
+        let exponent = &text[start + 1..];
+        let (sign, exponent_digits, digits_start) = match exponent.chars().next() {
+            Some(sign @ ('+' | '-')) => (Some(sign), &exponent[1..], start + 2),
+            _ => (None, exponent, start + 1),
+        };
+
+        if exponent_digits.is_empty() {
+            return Err(NumberError::new("Expected digits in the exponent".to_string(), digits_start));
+        }
+
+        digits.push('e');
+        digits.extend(sign);
+        digits.push_str(&strip_separators(exponent_digits, 10, digits_start)?);
+    }
+
+    match digits.parse::<f32>() {
+        Ok(value) if value.is_finite() => Ok(Number::Float(value)),
+        _ => Err(NumberError::new("Float literal is too large".to_string(), 0)),
+    }
+}
+
+/// Read the digits of an integer in a radix, without a prefix or sign
+pub fn parse_integer(text: &str, radix: u32) -> Result<i32, NumberError> {
+    parse_signed_integer(text, radix, false)
+}
+
+/// Read the digits of an integer in a radix, without a prefix, negating
+/// them if `negative`. The sign is applied before the range is checked, as
+/// the smallest integer is one further from zero than the largest.
+pub fn parse_signed_integer(text: &str, radix: u32, negative: bool) -> Result<i32, NumberError> {
+    let digits = strip_separators(text, radix, 0)?;
+
+    if digits.is_empty() {
+        return Err(NumberError::new(format!("Expected {} digits", radix_name(radix)), 0));
+    }
+
+    // parse as unsigned so that literals such as 0xFFFFFFFF are reported as too large
+    let value = u32::from_str_radix(&digits, radix).ok().map(|value| match negative {
+        true => -i64::from(value),
+        false => i64::from(value),
+    });
+
+    match value.and_then(|value| i32::try_from(value).ok()) {
+        Some(value) => Ok(value),
+        None if negative => Err(NumberError::new(format!("Integer is too small, the smallest is {}", i32::MIN), 0)),
+        None => Err(NumberError::new(format!("Integer is too large, the largest is {}", i32::MAX), 0)),
+    }
+}
+
+/// Remove `_` separators from digits, checking each is between two digits
+/// and that every digit is valid in the radix. `start` is the index of the
+/// digits within the whole text, used for the offset of errors.
+fn strip_separators(text: &str, radix: u32, start: usize) -> Result<String, NumberError> {
+    let mut digits = String::new();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in text.char_indices() {
+        let offset = start + index;
+
+        if c == '_' {
+            let next = text[index + 1..].chars().next();
+
+            if previous.is_none_or(|x| x == '_') || !next.is_some_and(|x| x.is_digit(radix)) {
+                return Err(NumberError::new("Separator '_' must be between two digits".to_string(), offset));
+            }
+        } else if c.is_digit(radix) {
+            digits.push(c);
+        } else {
+            return Err(NumberError::new(format!("Invalid digit '{}' in {} number", c, radix_name(radix)), offset));
+        }
+
+        previous = Some(c);
+    }
+
+    Ok(digits)
+}
\ No newline at end of file
@@ -10,10 +10,15 @@ pub struct Token {
 }
 
 /// Position of a token
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct TokenPosition {
     pub line: u32,
     pub column: u32,
+    /// Byte offset of the start of the token within the source.
+    /// Offsets are 32-bit like lines and columns, which keeps errors small.
+    pub start: u32,
+    /// Byte offset just past the end of the token within the source
+    pub end: u32,
 }
 
 /// Enum for differnt token types
@@ -23,7 +28,7 @@ pub enum TokenType {
     LeftParen, RightParen, LeftBrace, RightBrace,       // ( ) { }
     LeftSquare, RightSquare,                            // [ ]
     Comma, Dot, Minus, Plus, Semicolon, Slash, Star,    // , . - + ; / *
-    Caret, Underscore,                                  // ^ _
+    Caret, Underscore, Question, Colon,                 // ^ _ ? :
 
     // One or two character tokens
     Bang, BangEqual,                                    // ! !=
@@ -32,6 +37,8 @@ pub enum TokenType {
     Less, LessEqual,                                    // < <=
     // Arithmetic assignment tokens
     PlusEqual, MinusEqual, StarEqual, SlashEqual,       // += -= *= /=
+    Arrow,                                              // ->
+    Pipe,                                               // |>
 
     // Literals
     Integer(i32),                                       // 12345
@@ -58,9 +65,22 @@ pub enum Keyword {
     Func,
     Return,
     Assert,
+    Import,
+    From,
+    As,
+    For,
+    In,
+    Const,
+    Yield,
 }
 
 impl Keyword {
+    /// Every keyword, as written in source code
+    pub const NAMES: [&'static str; 17] = [
+        "let", "and", "not", "or", "if", "else", "elif", "func", "return",
+        "assert", "import", "from", "as", "for", "in", "const", "yield",
+    ];
+
     pub fn from(s: &str) -> Option<Keyword> {
         match s {
             "let" => Some(Keyword::Let),
@@ -73,16 +93,25 @@ impl Keyword {
             "func" => Some(Keyword::Func),
             "return" => Some(Keyword::Return),
             "assert" => Some(Keyword::Assert),
+            "import" => Some(Keyword::Import),
+            "from" => Some(Keyword::From),
+            "as" => Some(Keyword::As),
+            "for" => Some(Keyword::For),
+            "in" => Some(Keyword::In),
+            "const" => Some(Keyword::Const),
+            "yield" => Some(Keyword::Yield),
             _ => None,
         }
     }
 }
 
 impl Token {
-    pub fn new(r#value: TokenType, position: &LexerPosition) -> Token {
+    /// Token from the position of its first character
+    /// to the position just past its last
+    pub fn new(r#value: TokenType, start: &LexerPosition, end: &LexerPosition) -> Token {
         Token {
             r#value,
-            position: TokenPosition::from(position),
+            position: TokenPosition::between(start, end),
         }
     }
 }
@@ -92,6 +121,25 @@ impl TokenPosition {
         TokenPosition {
             line: position.line,
             column: position.column,
+            start: position.offset as u32,
+            end: position.offset as u32,
+        }
+    }
+
+    /// Position of the character at a lexer position, which is empty
+    /// at the end of the source as there is no character there
+    pub fn character(position: &LexerPosition) -> TokenPosition {
+        TokenPosition {
+            end: (position.offset + position.current_char.map_or(0, char::len_utf8)) as u32,
+            ..TokenPosition::from(position)
+        }
+    }
+
+    /// Position from one lexer position up to another
+    pub fn between(start: &LexerPosition, end: &LexerPosition) -> TokenPosition {
+        TokenPosition {
+            end: end.offset as u32,
+            ..TokenPosition::from(start)
         }
     }
 
@@ -99,6 +147,21 @@ impl TokenPosition {
         TokenPosition {
             line: 0,
             column: 0,
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Position spanning from the start of this position to the end of another
+    pub fn to(&self, other: &TokenPosition) -> TokenPosition {
+        if self.is_internal() {
+            return *other;
+        }
+
+        TokenPosition {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+            ..*self
         }
     }
 
@@ -137,6 +200,8 @@ impl std::fmt::Display for TokenType {
             TokenType::Star => "*".to_string(),
             TokenType::Caret => "^".to_string(),
             TokenType::Underscore => "_".to_string(),
+            TokenType::Question => "?".to_string(),
+            TokenType::Colon => ":".to_string(),
             TokenType::Bang => "!".to_string(),
             TokenType::BangEqual => "!=".to_string(),
             TokenType::Equal => "=".to_string(),
@@ -149,6 +214,8 @@ impl std::fmt::Display for TokenType {
             TokenType::MinusEqual => "-=".to_string(),
             TokenType::StarEqual => "*=".to_string(),
             TokenType::SlashEqual => "/=".to_string(),
+            TokenType::Arrow => "->".to_string(),
+            TokenType::Pipe => "|>".to_string(),
             TokenType::Integer(i) => i.to_string(),
             TokenType::Float(f) => f.to_string(),
             TokenType::String(s) => s.to_string(),
@@ -161,19 +228,28 @@ impl std::fmt::Display for TokenType {
     }
 }
 
-impl ToString for Keyword {
-    fn to_string(&self) -> String {
-        match self {
-            Keyword::Let => "let".to_string(),
-            Keyword::And => "and".to_string(),
-            Keyword::Not => "not".to_string(),
-            Keyword::Or => "or".to_string(),
-            Keyword::If => "if".to_string(),
-            Keyword::Else => "else".to_string(),
-            Keyword::Elif => "elif".to_string(),
-            Keyword::Func => "func".to_string(),
-            Keyword::Return => "return".to_string(),
-            Keyword::Assert => "assert".to_string(),
-        }
+impl std::fmt::Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Keyword::Let => "let",
+            Keyword::And => "and",
+            Keyword::Not => "not",
+            Keyword::Or => "or",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::Elif => "elif",
+            Keyword::Func => "func",
+            Keyword::Return => "return",
+            Keyword::Assert => "assert",
+            Keyword::Import => "import",
+            Keyword::From => "from",
+            Keyword::As => "as",
+            Keyword::For => "for",
+            Keyword::In => "in",
+            Keyword::Const => "const",
+            Keyword::Yield => "yield",
+        };
+
+        write!(f, "{}", text)
     }
 }
\ No newline at end of file
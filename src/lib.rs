@@ -0,0 +1,14 @@
+//! Lexes, parses, checks and runs Yum programs.
+//! The `yum_compiler` binary is a command-line interface to this library.
+
+pub mod lexer;
+pub mod errors;
+pub mod diagnostics;
+pub mod error_format;
+pub mod suggestions;
+pub mod parser;
+pub mod resolver;
+pub mod checker;
+pub mod interpreter;
+pub mod repl;
+pub mod cli;
\ No newline at end of file
@@ -1,59 +1,161 @@
-pub mod lexer;
-pub mod errors;
-pub mod parser;
-pub mod interpreter;
+use std::{env, io::{ErrorKind, IsTerminal, Write}, rc::Rc};
 
-use std::env;
+use yum_compiler::{checker, cli, error_format, interpreter, lexer, parser, repl, resolver};
+use yum_compiler::cli::{Arguments, Command, Source};
+use yum_compiler::error_format::ErrorFormat;
+use yum_compiler::errors::{exit_codes, Error, SourceFile};
+use yum_compiler::parser::nodes::Node;
 
-use errors::Error;
-use parser::nodes::Node;
-
-fn compile(source: String) -> Result<Node, Error> {
+/// Lex and parse a program, giving every syntax error found
+fn compile(source: String) -> Result<Node, Vec<Error>> {
     let mut lexer = lexer::Lexer::new(source);
-    let tokens = lexer.make_tokens()?;
-
-    // println!("Tokens: {:#?}", tokens);
+    let tokens = lexer.make_tokens().map_err(|e| vec![e])?;
 
     let mut parser = parser::Parser::new(tokens);
-    let ast = parser.parse();
-    ast
+    parser.parse()
 }
 
-fn run(ast: Node) -> Result<(), Error> {
-    let mut interpreter = interpreter::Interpreter::new();
+fn run(ast: Node, filename: &str, args: Vec<String>, max_call_depth: usize) -> Result<(), Error> {
+    let mut interpreter = interpreter::Interpreter::with_file(filename);
+    interpreter.set_args(args);
+    interpreter.set_max_call_depth(max_call_depth);
     interpreter.run(ast)
 }
 
-fn main() {
-    let filename = match env::args().nth(1) {
-        Some(filename) => filename,
-        None => {
-            println!("Usage: {} <filename>", env::args().nth(0).unwrap());
-            return;
-        }
-    };
+/// Check a program without running it.
+/// Names are resolved first, and types are only checked if they all exist.
+/// The modules it imports are checked once the program itself has no errors.
+fn analyse(ast: &Node, filename: &str) -> Result<checker::Signatures, Vec<Error>> {
+    resolver::resolve(ast)?;
+    let signatures = checker::check(ast)?;
+    interpreter::modules::check_imports(ast, filename)?;
+    Ok(signatures)
+}
+
+/// Report errors on stderr in the order they occur in the source, with the
+/// source lines they occurred on unless another format is asked for,
+/// giving the code to exit with for the first of them
+fn report(errors: Vec<Error>, file: &Rc<SourceFile>, format: ErrorFormat) -> i32 {
+    let errors: Vec<Error> = errors.into_iter().map(|e| e.with_file(file)).collect();
+    let code = errors.iter().min_by_key(|e| (e.position.line, e.position.column)).map_or(exit_codes::RUNTIME, |e| e.exit_code());
+    let colour = std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+    eprintln!("{}", error_format::render(&errors, format, colour));
+    code
+}
 
-    let source_code = match std::fs::read_to_string(filename) {
-        Ok(source_code) => source_code,
+/// Read the source code of a program, reporting why it could not be read
+fn read(source: &Source) -> Result<Rc<SourceFile>, i32> {
+    match source.read() {
+        Ok(text) => Ok(SourceFile::new(source.name(), &text)),
         Err(error) => {
-            println!("Error while reading source code: {}", error);
-            return;
+            eprintln!("Error while reading source code from {}: {}", source.name(), error);
+            Err(exit_codes::NO_INPUT)
         }
-    };
+    }
+}
 
-    let ast = match compile(source_code) {
-        Ok(x) => x,
+/// Write a command's output to stdout. Output stops quietly once whatever it
+/// is piped into stops reading, as with `yum_compiler tokens a.yum | head`.
+fn write_output(write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>) -> Result<(), i32> {
+    let mut stdout = std::io::stdout().lock();
+
+    match write(&mut stdout).and_then(|_| stdout.flush()) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
         Err(e) => {
-            println!("{}", e);
-            return;
+            eprintln!("Error while writing output: {}", e);
+            Err(exit_codes::RUNTIME)
         }
-    };
+    }
+}
+
+/// Lex, parse and analyse a program, reporting any errors
+fn load(file: &Rc<SourceFile>, format: ErrorFormat) -> Result<(Node, checker::Signatures), i32> {
+    let ast = compile(file.text.clone()).map_err(|errors| report(errors, file, format))?;
+
+    // refuse to run programs with undefined names or mismatched types
+    analyse(&ast, &file.name).map(|signatures| (ast, signatures)).map_err(|errors| report(errors, file, format))
+}
 
-    // println!("{:#?}", ast);
+/// Carry out a command, giving the code to exit with if it fails
+fn execute(arguments: Arguments) -> Result<(), i32> {
+    let format = arguments.error_format;
 
-    // run
-    match run(ast) {
-        Ok(_) => println!("Program exited successfully"),
-        Err(e) => println!("{}", e)
+    match arguments.command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Version => println!("yum_compiler {}", env!("CARGO_PKG_VERSION")),
+        Command::Repl => repl::run(),
+
+        Command::Tokens(source) => {
+            let file = read(&source)?;
+            let tokens = lexer::Lexer::new(file.text.clone()).make_tokens().map_err(|e| report(vec![e], &file, format))?;
+
+            write_output(|out| {
+                for token in tokens {
+                    writeln!(out, "{}:{}\t{:?}", token.position.line, token.position.column, token.value)?;
+                }
+                Ok(())
+            })?;
+        },
+
+        Command::Ast(source) => {
+            let file = read(&source)?;
+            let tokens = lexer::Lexer::new(file.text.clone()).make_tokens().map_err(|e| report(vec![e], &file, format))?;
+
+            // the tree is printed even with syntax errors, with error nodes in place of the statements which had them
+            let (ast, errors) = parser::Parser::new(tokens).parse_partial();
+            write_output(|out| writeln!(out, "{:#?}", ast))?;
+
+            if !errors.is_empty() {
+                return Err(report(errors, &file, format));
+            }
+        },
+
+        Command::Check(source) => {
+            let (_, signatures) = load(&read(&source)?, format)?;
+
+            for (name, signature) in signatures {
+                println!("{}: {}", name, signature);
+            }
+            println!("No errors found");
+        },
+
+        Command::Run { source, args } => {
+            let file = read(&source)?;
+            let (ast, _) = load(&file, format)?;
+
+            match run(ast, &file.name, args, arguments.max_call_depth) {
+                Ok(_) => {},
+                // `exit` stops the program without an error
+                Err(e) if e.is_exit() => return match e.exit_code() {
+                    exit_codes::SUCCESS => Ok(()),
+                    code => Err(code),
+                },
+                Err(e) => return Err(report(vec![e], &file, format)),
+            };
+
+            if arguments.verbose {
+                println!("Program exited successfully");
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let arguments = match cli::parse_args(env::args().skip(1).collect()) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            std::process::exit(exit_codes::USAGE);
+        }
     };
+
+    // deep recursion and nesting are reported as errors, rather than overflowing the stack
+    if let Err(code) = interpreter::with_stack(|| execute(arguments)) {
+        // output printed without a newline would otherwise be lost
+        let _ = std::io::stdout().flush();
+        std::process::exit(code);
+    }
 }
\ No newline at end of file
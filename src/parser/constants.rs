@@ -0,0 +1,62 @@
+//! Evaluates constant initialisers while parsing. Initialisers made only of
+//! literals and previously declared constants are computed once, and replaced
+//! by a literal node holding the result.
+
+use std::collections::HashMap;
+
+use super::nodes::{Node, NodeVisit, NumberNode, StringNode};
+use crate::{
+    interpreter::{symbol_table::SymbolTable, symbols::{Symbol, SymbolType}},
+    lexer::tokens::{Token, TokenType},
+};
+
+/// Fold a constant initialiser into a literal if possible.
+/// Returns the folded node, and its value if it was folded.
+/// Initialisers which fail to evaluate, such as `1 / 0`, are left
+/// unfolded so that the error is raised where the constant is declared.
+pub fn fold(node: Node, constants: &HashMap<String, Symbol>) -> (Node, Option<Symbol>) {
+    if !is_constant_expr(&node, constants) {
+        return (node, None);
+    }
+
+    // evaluate using only the known constants
+    let mut symbol_table = SymbolTable::new_global();
+    for (name, value) in constants {
+        symbol_table.symbols.insert(name.clone(), value.clone());
+    }
+
+    let value = match node.visit(&mut symbol_table) {
+        Ok(value) => value,
+        Err(_) => return (node, None),
+    };
+    let position = node.get_position();
+
+    let token_type = match value.value {
+        SymbolType::Integer(i) => TokenType::Integer(i),
+        SymbolType::Float(f) => TokenType::Float(f),
+        SymbolType::String(ref s) => TokenType::String(s.clone()),
+        _ => return (node, None),
+    };
+
+    let token = Token { value: token_type, position };
+    let literal = match token.value {
+        TokenType::String(_) => Node::StringNode(Box::new(StringNode::new(token))),
+        _ => Node::NumberNode(Box::new(NumberNode::new(token))),
+    };
+
+    (literal, Some(value))
+}
+
+/// Whether an expression only contains literals and known constants
+fn is_constant_expr(node: &Node, constants: &HashMap<String, Symbol>) -> bool {
+    match node {
+        Node::NumberNode(_) | Node::StringNode(_) => true,
+        Node::BinOpNode(node) => is_constant_expr(&node.left_node, constants) && is_constant_expr(&node.right_node, constants),
+        Node::UnaryOpNode(node) => is_constant_expr(&node.right, constants),
+        Node::VarAccessNode(node) => match node.identifier.value {
+            TokenType::Identifier(ref name) => constants.contains_key(name),
+            _ => false,
+        },
+        _ => false,
+    }
+}
\ No newline at end of file
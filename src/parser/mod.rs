@@ -1,37 +1,80 @@
 //! Parses a token stream and outputs an abstract syntax tree.
 
 pub mod nodes;
+mod constants;
 
 use nodes::{
     Node, 
     NumberNode,
     StringNode,
     BinOpNode, 
+    CompareNode,
+    Comparison,
     UnaryOpNode,
     VarAssignmentNode,
+    ConstAssignmentNode,
     VarArithmeticAssignmentNode,
     VarAccessNode,
     IfExprNode,
     FuncDefNode,
+    func_def_node_mod::ParameterNode,
     FuncCallNode,
     ListExprNode,
+    ListComprehensionNode,
     StatementsNode,
     ReturnNode,
+    YieldNode,
     AssertNode,
+    TryNode,
+    MethodCallNode,
+    AttributeAccessNode,
+    ImportNode,
+    TupleExprNode,
+    DestructuringAssignmentNode,
+    ForExprNode,
+    ErrorNode,
+    Pattern,
+    NodeVisit,
 };
 
-use crate::lexer::tokens::{Token, TokenType, Keyword};
+use std::collections::HashMap;
+
+use crate::lexer::tokens::{Token, TokenPosition, TokenType, Keyword};
 use crate::errors::{Error};
+use crate::suggestions;
+use crate::interpreter::symbols::Symbol;
 
 /// Parses the tokens into an AST
 pub struct Parser {
     tokens: Vec<Token>,
     token_index: usize,
     current_token: Option<Token>,
+    /// Values of constants declared so far, used to fold constant initialisers
+    constants: HashMap<String, Symbol>,
+    /// Errors of statements which could not be parsed
+    errors: Vec<Error>,
+    /// Levels the expression being parsed is nested within others
+    depth: usize,
+    /// Operators, calls and accesses chained one after another on the way
+    /// to the expression being parsed
+    chained: usize,
 }
 
 type GrammarOutput = Result<Node, Error>;
 
+/// Most levels expressions may be nested within each other. Brackets, unary
+/// operators and `^` add a level. The tree is visited recursively, so deeper
+/// ones could overflow the stack.
+pub const MAX_NESTING: usize = 200;
+
+/// Most operators, calls and accesses which may be chained one after another,
+/// as in `a + b + c` or `x.f().g()`. Each makes the tree a level deeper, but
+/// takes far less of the stack to visit than a level of nesting.
+pub const MAX_CHAIN: usize = 10_000;
+
+/// Positional and keyword arguments of a call
+type CallArguments = (Vec<Node>, Vec<(Token, Node)>);
+
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
         match tokens.len() {
@@ -43,30 +86,56 @@ impl Parser {
                     tokens,
                     token_index,
                     current_token,
+                    constants: HashMap::new(),
+                    errors: Vec::new(),
+                    depth: 0,
+                    chained: 0,
                 }
             }
         }
     }
 
-    pub fn parse(&mut self) -> GrammarOutput {
-        let res = self.gr_statements();
+    /// Parse the whole program, giving every syntax error found if it has any
+    pub fn parse(&mut self) -> Result<Node, Vec<Error>> {
+        let (ast, errors) = self.parse_partial();
 
-        if match self.get_current_token() {
-            Some(token) => token.value != TokenType::EOF,
-            None => true,
-        } {
-            return Err(Error::new_parser(
-                format!("Unexpected token: {:?}, expected EOF", self.get_current_token().unwrap().value),
-                &self.get_current_token_err()?.position,
-            ));
-        } else {
-            return res;
+        match errors.is_empty() {
+            true => Ok(ast),
+            false => Err(errors),
         }
     }
 
+    /// Parse the whole program, continuing past syntax errors.
+    /// Statements which could not be parsed are left in the tree as error nodes,
+    /// and their errors are given in the order they were found.
+    pub fn parse_partial(&mut self) -> (Node, Vec<Error>) {
+        let mut statements: Vec<Node> = Vec::new();
+
+        loop {
+            if let Ok(Node::StatementsNode(node)) = self.gr_statements() {
+                statements.extend(node.statements);
+            }
+
+            // statements stop at closing brackets, which are unmatched here
+            match self.get_current_token() {
+                Some(token) if token.value != TokenType::EOF => {
+                    self.errors.push(Error::new_parser(
+                        format!("Unexpected token: {:?}, expected EOF", token.value),
+                        &token.position,
+                    ));
+                    self.advance();
+                },
+                _ => break,
+            }
+        }
+
+        let ast = Node::StatementsNode(Box::new(StatementsNode::new(statements)));
+        (ast, std::mem::take(&mut self.errors))
+    }
+
     /// Attempt to get a token at a given index
     /// Returns None if no tokens are found
-    fn try_get_token(tokens: &Vec<Token>, pos: usize) -> Option<Token> {
+    fn try_get_token(tokens: &[Token], pos: usize) -> Option<Token> {
         let token: Option<&Token> = tokens.get(pos);
         match token {
             Some(token) => {
@@ -101,7 +170,6 @@ impl Parser {
     }
 
     /// Peek at the next token in the stream
-    #[allow(dead_code)]
     fn peek_token(&self) -> Result<Token, Error> {
         match Parser::try_get_token(&self.tokens, self.token_index + 1) {
             Some(token) => Ok(token),
@@ -116,10 +184,7 @@ impl Parser {
 
     /// Check whether the end of file has been reached
     fn reached_eof(&self) -> bool {
-        match self.current_token {
-            Some(_) => false,
-            None => true,
-        }
+        self.current_token.is_none()
     }
 
     /// Return a new error when the parser expects a
@@ -151,6 +216,46 @@ impl Parser {
         }
     }
 
+    /// Go a level deeper into nested expressions, failing past `MAX_NESTING`
+    /// levels around the innermost expression.
+    /// The caller restores the depth once it has finished with the level.
+    fn deepen(&mut self, position: &TokenPosition) -> Result<(), Error> {
+        if self.depth > MAX_NESTING {
+            return Err(Error::new_parser(
+                format!("Expression is nested more than {} levels deep", MAX_NESTING),
+                position,
+            ).with_note("parts of the expression can be given names with `let`".to_string()));
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Add an operator, call or access to the chain being parsed, failing
+    /// past `MAX_CHAIN` of them. The caller restores the count once it has
+    /// finished with the chain.
+    fn chain(&mut self, position: &TokenPosition) -> Result<(), Error> {
+        if self.chained >= MAX_CHAIN {
+            return Err(Error::new_parser(
+                format!("Expression chains more than {} operations", MAX_CHAIN),
+                position,
+            ).with_note("parts of the expression can be given names with `let`".to_string()));
+        }
+
+        self.chained += 1;
+        Ok(())
+    }
+
+    /// Parse a grammar rule a level deeper into nested expressions
+    fn nested(&mut self, rule: impl FnOnce(&mut Parser) -> GrammarOutput) -> GrammarOutput {
+        let depth = self.depth;
+        self.deepen(&self.get_current_token_err()?.position)?;
+
+        let result = rule(self);
+        self.depth = depth;
+        result
+    }
+
     // ================ Grammar ================
     // Located in src/grammar.txt
     // =========================================
@@ -165,8 +270,18 @@ impl Parser {
                 break;
             } else {
                 // DEBUG: println!("Getting statement from expression {:?}", self.get_current_token());
-                statements.push(self.gr_statement()?);
-
+                let start = self.token_index;
+                let (depth, chained) = (self.depth, self.chained);
+
+                match self.gr_statement() {
+                    Ok(statement) => statements.push(statement),
+                    Err(e) => {
+                        // keep going from the next statement, to find any other errors
+                        (self.depth, self.chained) = (depth, chained);
+                        self.errors.push(e);
+                        statements.push(self.synchronise(start));
+                    },
+                }
             }
         };
 
@@ -205,14 +320,147 @@ impl Parser {
                 
                 Ok(Node::ReturnNode(Box::new(ReturnNode::new(expr))))
             }
+            TokenType::Keyword(Keyword::Yield) => {
+                // yield statement, making the enclosing function a generator
+                self.advance();
+                let value = self.gr_expr()?;
+                Ok(Node::YieldNode(Box::new(YieldNode::new(value, current_tok))))
+            }
+            TokenType::Keyword(Keyword::Import) | TokenType::Keyword(Keyword::From) => {
+                self.gr_import()
+            }
             _ => {
                 self.gr_expr()
             }
+        }?;
+
+        if let Err(e) = self.expect(TokenType::Semicolon) {
+            return Err(self.misspelt_keyword(e, &statement));
+        }
+
+        self.advance();
+        Ok(statement)
+    }
+
+    /// A misspelt keyword starting a statement, such as `fucn f() {...}`, is
+    /// parsed as a name followed by what should come after the keyword.
+    /// Point out the name if it is close to a keyword.
+    fn misspelt_keyword(&self, error: Error, statement: &Node) -> Error {
+        let identifier = match statement {
+            Node::VarAccessNode(node) => &node.identifier,
+            _ => return error,
         };
 
-        self.expect(TokenType::Semicolon)?;
+        match identifier.value {
+            TokenType::Identifier(ref name) if !suggestions::closest(name, Keyword::NAMES).is_empty() => error
+                .with_label(&identifier.position, "not a keyword".to_string())
+                .with_suggestions(name, Keyword::NAMES),
+            _ => error,
+        }
+    }
+
+    /// Skip the rest of a statement which could not be parsed, from the token
+    /// it started at. Stops after the `;` ending the statement, or before the
+    /// `}` closing the block the statement is in, so that the block can end.
+    /// Only braces are counted, as `;` cannot be inside any other brackets,
+    /// so an unclosed `(` does not skip the rest of the program.
+    fn synchronise(&mut self, start: usize) -> Node {
+        let brace_depth = |token_type: &TokenType| match token_type {
+            TokenType::LeftBrace => 1,
+            TokenType::RightBrace => -1,
+            _ => 0,
+        };
+
+        // blocks opened by the statement before the error which are still open
+        let mut depth: i32 = self.tokens[start..self.token_index].iter().map(|x| brace_depth(&x.value)).sum::<i32>().max(0);
+
+        let first = self.tokens[start].position;
+        let mut last = first;
+
+        while let Some(token) = self.get_current_token() {
+            match token.value {
+                TokenType::EOF => break,
+                TokenType::RightBrace if depth == 0 => break,
+                TokenType::Semicolon if depth == 0 => {
+                    self.advance();
+                    break;
+                },
+                ref token_type => depth += brace_depth(token_type),
+            }
+
+            last = token.position;
+            self.advance();
+        }
+
+        Node::ErrorNode(Box::new(ErrorNode::new(first.to(&last))))
+    }
+
+    /// Import statement
+    /// Either `import "path" (as name)?` or `from "path" import name, ...`
+    fn gr_import(&mut self) -> GrammarOutput {
+        let is_from = self.get_current_token_err()?.value == TokenType::Keyword(Keyword::From);
         self.advance();
-        statement
+
+        // expect the module path
+        let path = self.get_current_token_err()?;
+        match path.value {
+            TokenType::String(_) => { self.advance(); },
+            _ => return Err(Error::new_parser(
+                format!("Expected module path, got {:?}", path.value),
+                &path.position,
+            )),
+        }
+
+        let mut alias = None;
+        let mut names = Vec::new();
+
+        if is_from {
+            self.expect(TokenType::Keyword(Keyword::Import))?;
+            self.advance();
+
+            // one or more names separated by commas
+            loop {
+                names.push(self.gr_identifier()?);
+
+                if self.get_current_token_err()?.value == TokenType::Comma {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+        } else if self.get_current_token_err()?.value == TokenType::Keyword(Keyword::As) {
+            self.advance();
+            alias = Some(self.gr_identifier()?);
+        }
+
+        Ok(Node::ImportNode(Box::new(ImportNode::new(path, alias, names))))
+    }
+
+    /// Optional type annotation, e.g. `: int`
+    /// Returns the name of the type
+    fn gr_annotation(&mut self) -> Result<Option<Token>, Error> {
+        if self.get_current_token_err()?.value != TokenType::Colon {
+            return Ok(None);
+        }
+
+        self.advance();
+        Ok(Some(self.gr_identifier()?))
+    }
+
+    /// Expect an identifier and advance past it
+    fn gr_identifier(&mut self) -> Result<Token, Error> {
+        let token = self.get_current_token_err()?;
+
+        match token.value {
+            TokenType::Identifier(_) => {
+                self.advance();
+                Ok(token)
+            },
+            _ => Err(Error::new_parser(
+                format!("Expected identifier, got {:?}", token.value),
+                &token.position,
+            )),
+        }
     }
 
     /// Expression
@@ -226,6 +474,17 @@ impl Parser {
                 // DEBUG: println!("Expecting variable assignment");
                 self.advance();
 
+                // destructuring assignment, e.g. `let (q, r) = ...`
+                if [TokenType::LeftParen, TokenType::LeftSquare].contains(&self.get_current_token_err()?.value) {
+                    let pattern = self.gr_pattern()?;
+
+                    self.expect(TokenType::Equal)?;
+                    self.advance();
+
+                    let expr = self.gr_expr()?;
+                    return Ok(Node::DestructuringAssignmentNode(Box::new(DestructuringAssignmentNode::new(pattern, expr))));
+                }
+
                 // ensure the next token is a variable name (identifier)
                 let var_name_identifier = match self.get_current_token() {
                     // check if a token exists at the current position
@@ -254,6 +513,12 @@ impl Parser {
 
                 // DEBUG: println!("Got identifier: {:?}", var_name_identifier);
 
+                // optional type annotation, which must be followed by a plain assignment
+                let annotation = self.gr_annotation()?;
+                if annotation.is_some() {
+                    self.expect(TokenType::Equal)?;
+                }
+
                 // ensure the next token is an equals sign
                 match self.get_current_token_err()?.value {
                     TokenType::Equal => {
@@ -266,7 +531,7 @@ impl Parser {
                         let expr = self.gr_expr()?;
 
                         // DEBUG: println!("Got expression");
-                        Ok(Node::VarAssignmentNode(Box::new(VarAssignmentNode::new(var_name_identifier, expr))))
+                        Ok(Node::VarAssignmentNode(Box::new(VarAssignmentNode::new(var_name_identifier, expr, annotation))))
                     }
 
                     TokenType::PlusEqual | TokenType::MinusEqual |
@@ -286,6 +551,25 @@ impl Parser {
                 
             },
 
+            TokenType::Keyword(Keyword::Const) => {
+                // expecting constant declaration
+                self.advance();
+
+                let identifier = self.gr_identifier()?;
+
+                self.expect(TokenType::Equal)?;
+                self.advance();
+
+                // literal-only initialisers are evaluated now
+                let (expr, value) = constants::fold(self.gr_expr()?, &self.constants);
+
+                if let (TokenType::Identifier(ref name), Some(value)) = (&identifier.value, value) {
+                    self.constants.insert(name.clone(), value);
+                }
+
+                Ok(Node::ConstAssignmentNode(Box::new(ConstAssignmentNode::new(identifier, expr))))
+            },
+
             TokenType::Keyword(Keyword::Assert) => {
                 // expecting assert statement
                 self.advance();
@@ -295,24 +579,67 @@ impl Parser {
             }
 
             _ => {
-                let mut left_node = self.gr_compare_expr()?;
+                let mut left_node = self.gr_logic_expr()?;
+                let chained = self.chained;
 
-                while !self.reached_eof() {
-                    if [TokenType::Keyword(Keyword::And), TokenType::Keyword(Keyword::Or)].contains(&self.get_current_token_err()?.value) {
-                        let op_token = self.get_current_token_err()?;
-                        self.advance();
-                        let right_node = self.gr_compare_expr()?;
-                        left_node = Node::BinOpNode(Box::new(BinOpNode::new(left_node, op_token, right_node)));
-                    } else {
-                        break;
-                    }
+                // pipelines have the lowest precedence, e.g. `x + 1 |> double`
+                while !self.reached_eof() && self.get_current_token_err()?.value == TokenType::Pipe {
+                    let pipe_token = self.get_current_token_err()?;
+                    self.chain(&pipe_token.position)?;
+                    self.advance();
+                    let right_node = self.gr_logic_expr()?;
+                    left_node = self.pipe(left_node, pipe_token, right_node)?;
                 }
 
+                self.chained = chained;
                 Ok(left_node)
             }
         }
     }
 
+    /// Pipe a value into a function, as its first argument.
+    /// `x |> f` becomes `f(x)`, `x |> f(y)` becomes `f(x, y)`
+    /// and `x |> module.f(y)` becomes `module.f(x, y)`.
+    fn pipe(&self, value: Node, pipe_token: Token, function: Node) -> GrammarOutput {
+        match function {
+            Node::VarAccessNode(_) => Ok(Node::FuncCallNode(Box::new(FuncCallNode::new(function, vec![value], Vec::new())))),
+            Node::FuncCallNode(mut call) => {
+                call.args.insert(0, value);
+                Ok(Node::FuncCallNode(call))
+            },
+            Node::AttributeAccessNode(access) => Ok(Node::MethodCallNode(Box::new(MethodCallNode::new(access.object, access.attribute, vec![value], Vec::new())))),
+            Node::MethodCallNode(mut call) => {
+                call.args.insert(0, value);
+                Ok(Node::MethodCallNode(call))
+            },
+            _ => Err(Error::new_parser(
+                format!("Expected a function or call after {}, found {}", pipe_token, function),
+                &function.get_position(),
+            )),
+        }
+    }
+
+    /// Logic Expression, of comparisons joined by `and` or `or`
+    fn gr_logic_expr(&mut self) -> GrammarOutput {
+        let mut left_node = self.gr_compare_expr()?;
+        let chained = self.chained;
+
+        while !self.reached_eof() {
+            if [TokenType::Keyword(Keyword::And), TokenType::Keyword(Keyword::Or)].contains(&self.get_current_token_err()?.value) {
+                let op_token = self.get_current_token_err()?;
+                self.chain(&op_token.position)?;
+                self.advance();
+                let right_node = self.gr_compare_expr()?;
+                left_node = Node::BinOpNode(Box::new(BinOpNode::new(left_node, op_token, right_node)));
+            } else {
+                break;
+            }
+        }
+
+        self.chained = chained;
+        Ok(left_node)
+    }
+
     /// Compare Expression
     fn gr_compare_expr(&mut self) -> GrammarOutput {
         // DEBUG: println!("Compare expression\t\t\t{:?}", self.get_current_token());
@@ -324,24 +651,43 @@ impl Parser {
                 // negation
                 self.advance();
                 // expect another compare expression
-                let right_node = self.gr_compare_expr()?;
+                let right_node = self.nested(Parser::gr_compare_expr)?;
                 Ok(Node::UnaryOpNode(Box::new(UnaryOpNode::new(current_token, right_node))))
             },
             _ => {
                 // find terms separated by operators
-                let mut left_node = self.gr_arithmetic_expr()?;
+                let first = self.gr_arithmetic_expr()?;
+                let mut comparisons: Vec<Comparison> = Vec::new();
 
                 while !self.reached_eof() {
-                    if [TokenType::EqualEqual, TokenType::BangEqual, TokenType::Less, TokenType::LessEqual, TokenType::Greater, TokenType::GreaterEqual].contains(&self.get_current_token_err()?.value) {
-                        let op_token = self.get_current_token_err()?;
-                        self.advance();
-                        let right_node = self.gr_arithmetic_expr()?;
-                        left_node = Node::BinOpNode(Box::new(BinOpNode::new(left_node, op_token, right_node)));
-                    } else {
-                        break;
-                    }
+                    let op_token = self.get_current_token_err()?;
+
+                    let negated = match op_token.value {
+                        TokenType::EqualEqual | TokenType::BangEqual | TokenType::Less | TokenType::LessEqual |
+                        TokenType::Greater | TokenType::GreaterEqual | TokenType::Keyword(Keyword::In) => false,
+                        // `not in`
+                        TokenType::Keyword(Keyword::Not) if self.peek_token()?.value == TokenType::Keyword(Keyword::In) => {
+                            self.advance();
+                            true
+                        },
+                        _ => break,
+                    };
+
+                    let op_token = self.get_current_token_err()?;
+                    self.advance();
+                    let right_node = self.gr_arithmetic_expr()?;
+                    comparisons.push(Comparison::new(op_token, negated, right_node));
+                }
+
+                // a single comparison is a binary operation, chains compare each pair
+                match comparisons.len() {
+                    0 => Ok(first),
+                    1 if !comparisons[0].is_membership() => {
+                        let comparison = comparisons.remove(0);
+                        Ok(Node::BinOpNode(Box::new(BinOpNode::new(first, comparison.operator, comparison.operand))))
+                    },
+                    _ => Ok(Node::CompareNode(Box::new(CompareNode::new(first, comparisons)))),
                 }
-                Ok(left_node)
             }
         }
 
@@ -353,10 +699,12 @@ impl Parser {
         // DEBUG: println!("Arith expression\t\t\t{:?}", self.get_current_token());
         // find terms separated by operators
         let mut left_node = self.gr_term()?;
+        let chained = self.chained;
 
         while !self.reached_eof() {
             if [TokenType::Plus, TokenType::Minus].contains(&self.get_current_token_err()?.value) {
                 let op_token = self.get_current_token_err()?;
+                self.chain(&op_token.position)?;
                 self.advance();
                 let right_node = self.gr_term()?;
                 left_node = Node::BinOpNode(Box::new(BinOpNode::new(left_node, op_token, right_node)));
@@ -365,6 +713,7 @@ impl Parser {
             }
         }
 
+        self.chained = chained;
         Ok(left_node)
     }
 
@@ -374,10 +723,12 @@ impl Parser {
         // find factors separated by operators
 
         let mut left_node = self.gr_factor()?;
+        let chained = self.chained;
 
         while !self.reached_eof() {
             if [TokenType::Star, TokenType::Slash].contains(&self.get_current_token_err()?.value) {
                 let op_token = self.get_current_token_err()?;
+                self.chain(&op_token.position)?;
                 self.advance();
                 let right_node = self.gr_factor()?;
                 left_node = Node::BinOpNode(Box::new(BinOpNode::new(left_node, op_token, right_node)));
@@ -386,6 +737,7 @@ impl Parser {
             }
         }
 
+        self.chained = chained;
         Ok(left_node)
     }
 
@@ -399,7 +751,7 @@ impl Parser {
             if self.get_current_token_err()?.value == TokenType::Caret {
                 let op_token = self.get_current_token_err()?;
                 self.advance();
-                let right_node = self.gr_factor()?;
+                let right_node = self.nested(Parser::gr_factor)?;
                 left_node = Node::BinOpNode(Box::new(BinOpNode::new(left_node, op_token, right_node)));
             } else {
                 break;
@@ -412,34 +764,99 @@ impl Parser {
     /// Call
     fn gr_call(&mut self) -> GrammarOutput {
         // DEBUG: println!("Call\t\t\t\t\t{:?}", self.get_current_token());
-        let left_node = self.gr_atom()?;
+        let mut left_node = self.nested(Parser::gr_atom)?;
+        let chained = self.chained;
 
-        let current_tok = self.get_current_token_err()?;
+        loop {
+            let current_tok = self.get_current_token_err()?;
 
-        if current_tok.value == TokenType::LeftParen {
-            // they are calling the identifier left_node
-            self.advance();
+            // each call, method or `?` contains those before it
+            if [TokenType::LeftParen, TokenType::Dot, TokenType::Question].contains(&current_tok.value) {
+                self.chain(&current_tok.position)?;
+            }
 
-            let mut args = Vec::new();
-            while self.get_current_token_err()?.value != TokenType::RightParen {
-                args.push(self.gr_expr()?);
-                
-                // if there is a comma, move on to the next argument
-                if self.get_current_token_err()?.value == TokenType::Comma {
+            left_node = match current_tok.value {
+                // they are calling the identifier left_node
+                TokenType::LeftParen => {
+                    let (args, kwargs) = self.gr_call_args()?;
+                    Node::FuncCallNode(Box::new(FuncCallNode::new(left_node, args, kwargs)))
+                },
+
+                // method call on left_node
+                TokenType::Dot => {
                     self.advance();
-                    continue;
-                }
-            };
 
-            self.expect(TokenType::RightParen)?;
-            self.advance();
+                    let method = self.get_current_token_err()?;
+                    match method.value {
+                        TokenType::Identifier(_) => { self.advance(); },
+                        _ => return Err(Error::new_parser(
+                            format!("Expected method name, got {:?}", method.value),
+                            &method.position,
+                        )),
+                    }
 
-            return Ok(Node::FuncCallNode(Box::new(FuncCallNode::new(left_node, args))));
+                    if self.get_current_token_err()?.value == TokenType::LeftParen {
+                        let (args, kwargs) = self.gr_call_args()?;
+                        Node::MethodCallNode(Box::new(MethodCallNode::new(left_node, method, args, kwargs)))
+                    } else {
+                        Node::AttributeAccessNode(Box::new(AttributeAccessNode::new(left_node, method)))
+                    }
+                },
+
+                // propagate errors from left_node
+                TokenType::Question => {
+                    self.advance();
+                    Node::TryNode(Box::new(TryNode::new(left_node, current_tok)))
+                },
+
+                _ => break,
+            };
         }
-        
+
+        self.chained = chained;
         Ok(left_node)
     }
 
+    /// Call arguments, positional then keyword (`name: value`)
+    /// Must be at the left parenthesis
+    fn gr_call_args(&mut self) -> Result<CallArguments, Error> {
+        self.expect(TokenType::LeftParen)?;
+        self.advance();
+
+        let mut args = Vec::new();
+        let mut kwargs = Vec::new();
+        while self.get_current_token_err()?.value != TokenType::RightParen {
+            let current_tok = self.get_current_token_err()?;
+            let is_keyword = matches!(current_tok.value, TokenType::Identifier(_))
+                && self.peek_token()?.value == TokenType::Colon;
+
+            if is_keyword {
+                // skip the name and colon
+                self.advance();
+                self.advance();
+                kwargs.push((current_tok, self.gr_expr()?));
+            } else if kwargs.is_empty() {
+                args.push(self.gr_expr()?);
+            } else {
+                return Err(Error::new_parser(
+                    "Positional argument cannot follow keyword arguments".to_string(),
+                    &current_tok.position,
+                ));
+            }
+            
+            // if there is a comma, move on to the next argument
+            if self.get_current_token_err()?.value == TokenType::Comma {
+                self.advance();
+                continue;
+            }
+        };
+
+        self.expect(TokenType::RightParen)?;
+        self.advance();
+
+        Ok((args, kwargs))
+    }
+
     /// Atom
     fn gr_atom(&mut self) -> GrammarOutput {
         // DEBUG: println!("Atom\t\t\t\t\t{:?}", self.get_current_token());
@@ -467,15 +884,28 @@ impl Parser {
                     TokenType::Plus | TokenType::Minus => {
                         let unary_op = self.get_current_token_err()?;
                         self.advance();
-                        let factor = self.gr_atom()?;
+                        let factor = self.nested(Parser::gr_atom)?;
                         let unary_op_node = UnaryOpNode::new(unary_op, factor);
                         Ok(Node::UnaryOpNode(Box::new(unary_op_node)))
                     },
 
-                    // if it is a left parenthesis, return a nested expression
+                    // if it is a left parenthesis, return a nested expression or a tuple
                     TokenType::LeftParen => {
                         self.advance();
+
+                        // empty tuple
+                        if self.get_current_token_err()?.value == TokenType::RightParen {
+                            self.advance();
+                            return Ok(Node::TupleExprNode(Box::new(TupleExprNode::new(Vec::new(), token))));
+                        }
+
                         let expr = self.gr_expr()?;
+
+                        // a comma after the first expression makes a tuple
+                        if self.get_current_token_err()?.value == TokenType::Comma {
+                            return self.gr_tuple_expr(token, expr);
+                        }
+
                         match self.get_current_token() {
                             Some(token) => {
                                 if token.value == TokenType::RightParen {
@@ -488,15 +918,13 @@ impl Parser {
                                     ))
                                 }
                             }
-                            None => {
-                                return Err(self.error_missing_token())
-                            }
+                            None => Err(self.error_missing_token()),
                         }
                     },
 
                     TokenType::LeftSquare => {
                         self.advance();
-                        self.gr_list_expr()
+                        self.gr_list_expr(token)
                     }
 
                     // if it is an identifier, return a variable access node
@@ -518,6 +946,12 @@ impl Parser {
                         self.gr_func_def()
                     }
 
+                    // For keyword
+                    TokenType::Keyword(Keyword::For) => {
+                        self.advance();
+                        self.gr_for_expr()
+                    }
+
                     // if no matches, return an error
                     _ => Err(Error::new_parser(
                         format!("Expected atom, found {:?}", token.value),
@@ -526,17 +960,23 @@ impl Parser {
                 }
             },
 
-            None => return Err(self.error_missing_token()),
+            None => Err(self.error_missing_token()),
         }
     }
 
-    fn gr_list_expr(&mut self) -> GrammarOutput {
+    fn gr_list_expr(&mut self, token: Token) -> GrammarOutput {
         // DEBUG: println!("List\t\t\t\t\t{:?}", self.get_current_token());
         let mut elements = Vec::new();
 
         while self.get_current_token_err()?.value != TokenType::RightSquare {
             elements.push(self.gr_expr()?);
 
+            // `[element for pattern in iterable]`
+            if elements.len() == 1 && self.get_current_token_err()?.value == TokenType::Keyword(Keyword::For) {
+                self.advance();
+                return self.gr_list_comprehension(token, elements.remove(0));
+            }
+
             if self.get_current_token_err()?.value == TokenType::Comma {
                 self.advance();
                 continue;
@@ -546,7 +986,128 @@ impl Parser {
         self.expect(TokenType::RightSquare)?;
         self.advance();
 
-        Ok(Node::ListExprNode(Box::new(ListExprNode::new(elements))))
+        Ok(Node::ListExprNode(Box::new(ListExprNode::new(elements, token))))
+    }
+
+    /// List comprehension
+    /// Must have parsed the element expression and advanced past 'for' keyword
+    fn gr_list_comprehension(&mut self, token: Token, element: Node) -> GrammarOutput {
+        let pattern = self.gr_pattern()?;
+
+        self.expect(TokenType::Keyword(Keyword::In))?;
+        self.advance();
+
+        let iterable = self.gr_expr()?;
+
+        // optional condition
+        let condition = match self.get_current_token_err()?.value {
+            TokenType::Keyword(Keyword::If) => {
+                self.advance();
+                Some(self.gr_expr()?)
+            },
+            _ => None,
+        };
+
+        self.expect(TokenType::RightSquare)?;
+        self.advance();
+
+        Ok(Node::ListComprehensionNode(Box::new(ListComprehensionNode::new(element, pattern, iterable, condition, token))))
+    }
+
+    /// Tuple expression
+    /// Must have parsed the first element, with the current token being the comma after it
+    fn gr_tuple_expr(&mut self, token: Token, first: Node) -> GrammarOutput {
+        let mut elements = vec![first];
+
+        while self.get_current_token_err()?.value == TokenType::Comma {
+            self.advance();
+
+            // allow a trailing comma, e.g. `(a,)`
+            if self.get_current_token_err()?.value == TokenType::RightParen {
+                break;
+            }
+
+            elements.push(self.gr_expr()?);
+        }
+
+        self.expect(TokenType::RightParen)?;
+        self.advance();
+
+        Ok(Node::TupleExprNode(Box::new(TupleExprNode::new(elements, token))))
+    }
+
+    /// Destructuring pattern
+    /// Either `name`, `(<pattern>, ...)` or `[<pattern>, ...]`
+    fn gr_pattern(&mut self) -> Result<Pattern, Error> {
+        let token = self.get_current_token_err()?;
+
+        let closing = match token.value {
+            TokenType::Identifier(_) => return Ok(Pattern::Identifier(self.gr_identifier()?)),
+            TokenType::LeftParen => TokenType::RightParen,
+            TokenType::LeftSquare => TokenType::RightSquare,
+            _ => return Err(Error::new_parser(
+                format!("Expected identifier or pattern, got {:?}", token.value),
+                &token.position,
+            )),
+        };
+
+        self.advance();
+
+        // zero or more patterns separated by commas
+        let mut patterns: Vec<Pattern> = Vec::new();
+
+        while self.get_current_token_err()?.value != closing {
+            patterns.push(self.gr_pattern()?);
+
+            if self.get_current_token_err()?.value == TokenType::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.expect(closing)?;
+        self.advance();
+
+        let pattern = match token.value {
+            TokenType::LeftParen => Pattern::Tuple(patterns, token),
+            _ => Pattern::List(patterns, token),
+        };
+
+        // a name can only be bound once
+        let names = pattern.identifiers();
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].iter().any(|x| x.value == name.value) {
+                return Err(Error::new_parser(
+                    format!("Duplicate name {} in pattern", name.value),
+                    &name.position,
+                ));
+            }
+        }
+
+        Ok(pattern)
+    }
+
+    /// For Expression
+    /// Must have advanced past 'for' keyword
+    fn gr_for_expr(&mut self) -> GrammarOutput {
+        let pattern = self.gr_pattern()?;
+
+        self.expect(TokenType::Keyword(Keyword::In))?;
+        self.advance();
+
+        let iterable = self.gr_expr()?;
+
+        // expect { statements }
+        self.expect(TokenType::LeftBrace)?;
+        self.advance();
+
+        let body = self.gr_statements()?;
+
+        self.expect(TokenType::RightBrace)?;
+        self.advance();
+
+        Ok(Node::ForExprNode(Box::new(ForExprNode::new(pattern, iterable, body))))
     }
 
     /// If Expression
@@ -565,53 +1126,64 @@ impl Parser {
         self.advance();
 
         // get expression to be evaluated if condition is true
-        let if_true = self.gr_expr()?;
+        let if_true = self.gr_branch()?;
 
         // expect curly braces
         self.expect(TokenType::RightBrace)?;
         self.advance();
 
         // optional else keyword
-        match self.get_current_token() {
-            Some(token) => {
-                match token.value {
-                    TokenType::Keyword(Keyword::Else) => {
-                        self.advance();
+        if let Some(Token { value: TokenType::Keyword(Keyword::Else), .. }) = self.get_current_token() {
+            self.advance();
 
-                        // DEBUG: println!("Found else keyword, advanced to {:?}", self.get_current_token());
+            // DEBUG: println!("Found else keyword, advanced to {:?}", self.get_current_token());
 
-                        // nested if expression
-                        if self.get_current_token_err()?.value == TokenType::Keyword(Keyword::If) {
-                            self.advance();
-                            // DEBUG: println!("Found nested if expression");
-                            else_case = Some(self.gr_if_expr()?);
-                            // DEBUG: println!("else_case: {:?}", else_case);
-                        } else {
-                            // DEBUG: println!("Found else expression");
-                            // expect curly braces
-                            self.expect(TokenType::LeftBrace)?;
-                            self.advance();
+            // nested if expression
+            if self.get_current_token_err()?.value == TokenType::Keyword(Keyword::If) {
+                self.advance();
+                // DEBUG: println!("Found nested if expression");
+                else_case = Some(self.nested(Parser::gr_if_expr)?);
+                // DEBUG: println!("else_case: {:?}", else_case);
+            } else {
+                // DEBUG: println!("Found else expression");
+                // expect curly braces
+                self.expect(TokenType::LeftBrace)?;
+                self.advance();
 
-                            // get expression to be evaluated if condition is false
-                            let if_false = self.gr_expr()?;
+                // get expression to be evaluated if condition is false
+                let if_false = self.gr_branch()?;
 
-                            // expect curly braces
-                            self.expect(TokenType::RightBrace)?;
-                            self.advance();
+                // expect curly braces
+                self.expect(TokenType::RightBrace)?;
+                self.advance();
 
-                            else_case = Some(if_false);
-                        }
-                    },
-                    _ => {},
-                }
-            },
-            None => {}
+                else_case = Some(if_false);
+            }
         }
 
         Ok(Node::IfExprNode(Box::new(IfExprNode::new(condition, if_true, else_case))))
 
     }
 
+    /// Body of an if expression branch, which is an expression
+    /// or a yield statement with an optional semicolon
+    fn gr_branch(&mut self) -> GrammarOutput {
+        let current_tok = self.get_current_token_err()?;
+
+        if current_tok.value != TokenType::Keyword(Keyword::Yield) {
+            return self.gr_expr();
+        }
+
+        self.advance();
+        let value = self.gr_expr()?;
+
+        if self.get_current_token_err()?.value == TokenType::Semicolon {
+            self.advance();
+        }
+
+        Ok(Node::YieldNode(Box::new(YieldNode::new(value, current_tok))))
+    }
+
     /// Function Definition
     fn gr_func_def(&mut self) -> GrammarOutput {
         // DEBUG: println!("Function definition\t\t\t{:?}", self.get_current_token());
@@ -627,50 +1199,46 @@ impl Parser {
 
         self.advance();
 
-        // expect () or (identifier) or (identifier, identifier, ...)
+        // expect () or (parameter) or (parameter, parameter, ...)
         self.expect(TokenType::LeftParen)?;
         self.advance();
 
-        let mut parameters: Vec<Token> = Vec::new();
-        
-        let current_tok = self.get_current_token_err()?;
+        let mut parameters: Vec<ParameterNode> = Vec::new();
 
-        match current_tok.value {
-            TokenType::Identifier(_) => {
-                parameters.push(current_tok);
-                self.advance();
-            },
-            _ => {}
-        }
+        if self.get_current_token_err()?.value != TokenType::RightParen {
+            // one or more parameters separated by commas
+            loop {
+                // DEBUG: println!("Expecting parameter");
+                let parameter = self.gr_parameter(&parameters)?;
+                parameters.push(parameter);
 
-        // one or more ", <identifier>"
-        while self.get_current_token_err()?.value == TokenType::Comma {
-            // DEBUG: println!("Expecting identifier");
-            self.advance();
-            let current_tok = self.get_current_token_err()?;
-
-            match current_tok.value {
-                TokenType::Identifier(_) => {
-                    parameters.push(current_tok);
+                if self.get_current_token_err()?.value == TokenType::Comma {
                     self.advance();
-                },
-                _ => {
-                    return Err(Error::new_parser(
-                        format!("Expected identifier, got {:?}", self.get_current_token_err()?.value),
-                        &self.get_current_token_err()?.position,
-                    ))
+                } else {
+                    break;
                 }
             }
-        };
+        }
 
         // expect right parenthesis
         self.expect(TokenType::RightParen)?;
         self.advance();
 
+        // optional return type
+        let return_type = if self.get_current_token_err()?.value == TokenType::Arrow {
+            self.advance();
+            Some(self.gr_identifier()?)
+        } else {
+            None
+        };
+
         // expect { expr }
         self.expect(TokenType::LeftBrace)?;
         self.advance();
 
+        // constants declared in the body are local to the function
+        let outer_constants = self.constants.clone();
+
         // check if the next char is a right brace, if not expect an expression before
         let body: Option<Node> = if self.get_current_token_err()?.value == TokenType::RightBrace {
             // DEBUG: println!("Expecting empty expressionn");
@@ -680,11 +1248,222 @@ impl Parser {
             Some(self.gr_statements()?)
         };
 
+        self.constants = outer_constants;
+
         // DEBUG: println!("Body for function: {:?}", body);
 
         self.expect(TokenType::RightBrace)?;
         self.advance();
 
-        Ok(Node::FuncDefNode(Box::new(FuncDefNode::new(identifier, parameters, body))))
+        Ok(Node::FuncDefNode(Box::new(FuncDefNode::new(identifier, parameters, body, return_type))))
     }
-}
\ No newline at end of file
+
+    /// Function parameter
+    /// Either `name`, `name = <expr>`, `*name` or a destructuring pattern
+    fn gr_parameter(&mut self, previous: &[ParameterNode]) -> Result<ParameterNode, Error> {
+        let variadic = self.get_current_token_err()?.value == TokenType::Star;
+        if variadic {
+            self.advance();
+        }
+
+        // destructured parameter, e.g. `(x, y)`
+        let pattern = match self.get_current_token_err()?.value {
+            TokenType::LeftParen | TokenType::LeftSquare if !variadic => Some(self.gr_pattern()?),
+            _ => None,
+        };
+
+        let identifier = match pattern {
+            Some(ref pattern) => pattern.token().clone(),
+            None => self.gr_identifier()?,
+        };
+
+        let annotation = self.gr_annotation()?;
+
+        // optional default value
+        let default = if self.get_current_token_err()?.value == TokenType::Equal {
+            if variadic {
+                return Err(Error::new_parser(
+                    "Variadic parameter cannot have a default value".to_string(),
+                    &self.get_current_token_err()?.position,
+                ));
+            }
+
+            self.advance();
+            Some(self.gr_expr()?)
+        } else {
+            None
+        };
+
+        if previous.iter().any(|x| x.variadic) {
+            return Err(Error::new_parser(
+                "Parameters cannot follow a variadic parameter".to_string(),
+                &identifier.position,
+            ));
+        }
+
+        let names = match pattern {
+            Some(ref pattern) => pattern.identifiers(),
+            None => vec![&identifier],
+        };
+
+        for name in names {
+            if previous.iter().flat_map(|x| x.identifiers()).any(|x| x.value == name.value) {
+                return Err(Error::new_parser(
+                    format!("Duplicate parameter {}", name.value),
+                    &name.position,
+                ));
+            }
+        }
+
+        if !variadic && default.is_none() && previous.iter().any(|x| x.default.is_some()) {
+            return Err(Error::new_parser(
+                format!("Parameter {} without a default cannot follow parameters with defaults", identifier.value),
+                &identifier.position,
+            ));
+        }
+
+        Ok(ParameterNode::new(identifier, default, variadic, pattern).annotated(annotation))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diagnostics::{self, MAX_ERRORS}, errors::ErrorType, interpreter, lexer::Lexer};
+
+    fn parse(text: &str) -> Result<Node, Vec<Error>> {
+        Parser::new(Lexer::new(text.to_string()).make_tokens().unwrap()).parse()
+    }
+
+    /// Statement of `1` inside `depth` brackets
+    fn brackets(depth: usize) -> String {
+        format!("{}1{};", "(".repeat(depth), ")".repeat(depth))
+    }
+
+    /// Statement adding `count` ones to 1
+    fn chain(count: usize) -> String {
+        format!("1{};", " + 1".repeat(count))
+    }
+
+    fn assert_too_deep(errors: Vec<Error>) {
+        assert_syntax_error(errors, "nested");
+    }
+
+    fn assert_syntax_error(errors: Vec<Error>, message: &str) {
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(matches!(errors[0].error_type, ErrorType::ParserError), "{:?}", errors[0]);
+        assert!(errors[0].error_message.contains(message), "{:?}", errors[0]);
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_is_parsed() {
+        interpreter::with_stack(|| {
+            assert!(parse(&brackets(MAX_NESTING)).is_ok());
+            assert!(parse(&format!("let a = {}1;", "-".repeat(MAX_NESTING))).is_ok());
+            assert!(parse(&chain(MAX_CHAIN)).is_ok());
+        });
+    }
+
+    #[test]
+    fn deep_brackets_are_a_syntax_error() {
+        interpreter::with_stack(|| {
+            assert_too_deep(parse(&brackets(MAX_NESTING + 1)).unwrap_err());
+            assert_too_deep(parse(&brackets(10_000)).unwrap_err());
+            assert_too_deep(parse(&format!("let a = {}1;", "-".repeat(MAX_NESTING + 1))).unwrap_err());
+            assert_too_deep(parse(&format!("let a = {}1;", "-".repeat(10_000))).unwrap_err());
+            assert_too_deep(parse(&format!("let a = {}1{};", "[".repeat(10_000), "]".repeat(10_000))).unwrap_err());
+            assert_too_deep(parse(&format!("1{};", " ^ 1".repeat(10_000))).unwrap_err());
+        });
+    }
+
+    #[test]
+    fn operator_chains_are_not_nesting() {
+        interpreter::with_stack(|| {
+            assert!(parse(&chain(MAX_NESTING * 2)).is_ok());
+            assert!(parse(&format!("let a = x{};", " |> f".repeat(MAX_NESTING * 2))).is_ok());
+            assert!(parse(&format!("let a = x{};", ".y".repeat(MAX_NESTING * 2))).is_ok());
+            // chains inside the deepest brackets
+            assert!(parse(&format!("{}1{}{};", "(".repeat(MAX_NESTING), " * 1".repeat(MAX_NESTING * 2), ")".repeat(MAX_NESTING))).is_ok());
+        });
+    }
+
+    #[test]
+    fn very_long_chains_are_a_syntax_error() {
+        interpreter::with_stack(|| {
+            assert_syntax_error(parse(&chain(MAX_CHAIN + 1)).unwrap_err(), "chains");
+            assert_syntax_error(parse(&format!("x{};", " |> f".repeat(MAX_CHAIN + 1))).unwrap_err(), "chains");
+            assert_syntax_error(parse(&format!("x{};", ".y".repeat(MAX_CHAIN + 1))).unwrap_err(), "chains");
+            // operators in different brackets are part of the same chain
+            assert_syntax_error(parse(&format!("1{} + ({}1);", " + 1".repeat(MAX_CHAIN / 2), "1 + ".repeat(MAX_CHAIN / 2))).unwrap_err(), "chains");
+        });
+    }
+
+    /// Line and column of each error
+    fn positions(errors: &[Error]) -> Vec<(u32, u32)> {
+        errors.iter().map(|x| (x.position.line, x.position.column)).collect()
+    }
+
+    #[test]
+    fn syntax_errors_are_collected_in_source_order() {
+        let errors = parse("let = 1; let = 2;\nlet a = 1;\nlet b = );\nlet c = ;").unwrap_err();
+        assert_eq!(positions(&errors), [(1, 5), (1, 14), (3, 9), (4, 9)]);
+    }
+
+    #[test]
+    fn parsing_resumes_after_the_statement_or_block_with_an_error() {
+        // after the `;` ending the statement
+        let errors = parse("let a = (1 + ;\nlet b = 2;\nlet = 3;").unwrap_err();
+        assert_eq!(positions(&errors), [(1, 14), (3, 5)]);
+
+        // before the `}` closing the block, which still ends the function
+        let errors = parse("func f() {\n    let a = )\n};\nlet = 3;").unwrap_err();
+        assert_eq!(positions(&errors), [(2, 13), (4, 5)]);
+
+        // statements after the error in the same block are parsed too
+        let errors = parse("func f() {\n    let = 1;\n    let = 2;\n};").unwrap_err();
+        assert_eq!(positions(&errors), [(2, 9), (3, 9)]);
+    }
+
+    #[test]
+    fn only_the_first_errors_are_shown() {
+        let source = "let = 1;\n".repeat(MAX_ERRORS + 5);
+        let errors = parse(&source).unwrap_err();
+        assert_eq!(errors.len(), MAX_ERRORS + 5);
+
+        let rendered = diagnostics::render_all(&errors, false);
+        assert_eq!(rendered.matches(&errors[0].error_message).count(), MAX_ERRORS);
+        assert!(rendered.ends_with("5 more errors were not shown"), "{}", rendered);
+    }
+
+    #[test]
+    fn constants_which_fail_to_evaluate_are_not_folded() {
+        for source in ["const A = 1 / 0;", "const A = 2 ^ 40;"] {
+            let ast = parse(source).unwrap();
+            assert!(format!("{:?}", ast).contains("BinOpNode"), "{:?}", ast);
+        }
+    }
+
+    #[test]
+    fn names_cannot_repeat_in_a_pattern() {
+        for source in ["let (a, a) = (1, 2);", "let [a, (b, a)] = [1, (2, 3)];", "for (x, x) in [] { x; };", "func f((x, x)) { return x; };"] {
+            let errors = parse(source).unwrap_err();
+            assert_eq!(errors.len(), 1, "{:?}", errors);
+            assert!(errors[0].error_message.starts_with("Duplicate name"), "{:?}", errors[0]);
+        }
+
+        assert!(parse("let (a, [b, c]) = (1, [2, 3]);").is_ok());
+    }
+
+    #[test]
+    fn statements_after_deep_nesting_are_parsed() {
+        // the error on the last line is only found if the lines before it are parsed
+        let source = format!("{}\nlet a = {}\nlet = 1;", brackets(10_000), brackets(MAX_NESTING));
+        interpreter::with_stack(|| {
+            let mut errors = parse(&source).unwrap_err();
+
+            assert_eq!(errors.len(), 2, "{:?}", errors);
+            let last = errors.pop().unwrap();
+            assert_eq!(last.position.line, 3, "{:?}", last);
+            assert_too_deep(errors);
+        });
+    }
+}
@@ -48,8 +48,8 @@ impl NodeVisit for AssertNode {
     }
 }
 
-impl ToString for AssertNode {
-    fn to_string(&self) -> String {
-        format!("assert({})", self.condition.to_string())
+impl std::fmt::Display for AssertNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "assert({})", self.condition)
     }
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, get_name_as_string};
+use crate::{interpreter::symbol_table::SymbolTable, lexer::tokens::TokenPosition};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Attribute access node, e.g. `utils.pi`
+#[derive(Debug, Clone)]
+pub struct AttributeAccessNode {
+    pub object: Node,
+    pub attribute: Token,
+}
+
+impl AttributeAccessNode {
+    pub fn new(object: Node, attribute: Token) -> AttributeAccessNode {
+        AttributeAccessNode {
+            object,
+            attribute,
+        }
+    }
+}
+
+impl NodeVisit for AttributeAccessNode {
+    fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+        let object = self.object.visit(symbol_table)?;
+        let attribute_name = get_name_as_string(self.attribute.clone())?;
+        object.get_attribute(&attribute_name, &self.get_position())
+    }
+
+    fn get_position(&self) -> TokenPosition {
+        self.attribute.position
+    }
+}
+
+impl std::fmt::Display for AttributeAccessNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.object, self.attribute)
+    }
+}
\ No newline at end of file
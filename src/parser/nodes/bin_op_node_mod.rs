@@ -1,6 +1,6 @@
 #[allow(unused_imports)]
 use super::{Node, NodeVisit};
-use crate::{interpreter::symbol_table::SymbolTable, lexer::tokens::{Keyword, TokenPosition}};
+use crate::{interpreter::symbol_table::SymbolTable, lexer::tokens::TokenPosition};
 #[allow(unused_imports)]
 use crate::{interpreter::symbols::Symbol, lexer::tokens::{Token, TokenType}, errors::{Error, ErrorType}};
 
@@ -26,30 +26,7 @@ impl NodeVisit for BinOpNode {
     fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
         let left_symbol = self.left_node.visit(symbol_table)?;
         let right_symbol = self.right_node.visit(symbol_table)?;
-        let op_token = self.op_token.clone();
-        let result = match op_token.value {
-            TokenType::Plus => left_symbol.add(&right_symbol)?,
-            TokenType::Minus => left_symbol.sub(&right_symbol)?,
-            TokenType::Star => left_symbol.mul(&right_symbol)?,
-            TokenType::Slash => left_symbol.div(&right_symbol)?,
-            TokenType::Caret => left_symbol.power(&right_symbol)?,
-            TokenType::Less => left_symbol.lt(&right_symbol)?,
-            TokenType::LessEqual => left_symbol.le(&right_symbol)?,
-            TokenType::Greater => left_symbol.gt(&right_symbol)?,
-            TokenType::GreaterEqual => left_symbol.ge(&right_symbol)?,
-            TokenType::EqualEqual => left_symbol.eq(&right_symbol)?,
-            TokenType::BangEqual => left_symbol.ne(&right_symbol)?,
-            TokenType::Keyword(Keyword::And) => left_symbol.and(&right_symbol)?,
-            TokenType::Keyword(Keyword::Or) => left_symbol.or(&right_symbol)?,
-            _ => {
-                return Err(Error::new_runtime(
-                    ErrorType::InvalidOperation,
-                    format!("Invalid operation: {:?}", op_token),
-                    &self.get_position(),
-                ));
-            }
-        };
-        Ok(result)
+        left_symbol.binary_operation(&self.op_token, &right_symbol)
     }
 
     fn get_position(&self) -> TokenPosition {
@@ -57,8 +34,8 @@ impl NodeVisit for BinOpNode {
     }
 }
 
-impl ToString for BinOpNode {
-    fn to_string(&self) -> String {
-        format!("({} {} {})", self.left_node.to_string(), self.op_token.to_string(), self.right_node.to_string())
+impl std::fmt::Display for BinOpNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({} {} {})", self.left_node, self.op_token, self.right_node)
     }
 }
\ No newline at end of file
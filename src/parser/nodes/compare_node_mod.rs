@@ -0,0 +1,93 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit};
+use crate::interpreter::symbols::SymbolType;
+use crate::lexer::tokens::{Keyword, TokenType};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Chain of comparisons, e.g. `a < b <= c` or `x not in xs`.
+/// A chain is true when every comparison in it is, with each operand
+/// evaluated at most once: `a < b < c` is `a < b and b < c`.
+#[derive(Debug, Clone)]
+pub struct CompareNode {
+    pub first: Node,
+    pub comparisons: Vec<Comparison>,
+}
+
+/// Comparison with the operand before it
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub operator: Token,
+    /// Whether the operator is `not in` rather than `in`
+    pub negated: bool,
+    pub operand: Node,
+}
+
+impl CompareNode {
+    pub fn new(first: Node, comparisons: Vec<Comparison>) -> CompareNode {
+        CompareNode {
+            first,
+            comparisons,
+        }
+    }
+}
+
+impl Comparison {
+    pub fn new(operator: Token, negated: bool, operand: Node) -> Comparison {
+        Comparison {
+            operator,
+            negated,
+            operand,
+        }
+    }
+
+    pub fn is_membership(&self) -> bool {
+        self.operator.value == TokenType::Keyword(Keyword::In)
+    }
+}
+
+impl NodeVisit for CompareNode {
+    fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        let mut left = self.first.visit(symbol_table)?;
+
+        for comparison in &self.comparisons {
+            let right = comparison.operand.visit(symbol_table)?;
+
+            let result = match comparison.is_membership() {
+                true => left.is_in(&right, symbol_table).map_err(|mut e| {
+                    e.position = comparison.operator.position;
+                    e
+                })? != comparison.negated,
+                false => !matches!(left.binary_operation(&comparison.operator, &right)?.value, SymbolType::Integer(0)),
+            };
+
+            // later operands are not evaluated once a comparison is false
+            if !result {
+                return Ok(Symbol::new(SymbolType::Integer(0), self.get_position()));
+            }
+
+            left = right;
+        }
+
+        Ok(Symbol::new(SymbolType::Integer(1), self.get_position()))
+    }
+
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.comparisons[0].operator.position
+    }
+}
+
+impl std::fmt::Display for CompareNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}", self.first)?;
+
+        for comparison in &self.comparisons {
+            match comparison.negated {
+                true => write!(f, " not {} {}", comparison.operator, comparison.operand)?,
+                false => write!(f, " {} {}", comparison.operator, comparison.operand)?,
+            }
+        }
+
+        write!(f, ")")
+    }
+}
\ No newline at end of file
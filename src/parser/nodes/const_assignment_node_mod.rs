@@ -0,0 +1,42 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, get_name_as_string};
+use crate::interpreter::symbols::SymbolType;
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Constant assignment node, e.g. `const LIMIT = 10;`
+#[derive(Debug, Clone)]
+pub struct ConstAssignmentNode {
+    pub identifier: Token,
+    pub value: Node,
+}
+
+impl ConstAssignmentNode {
+    pub fn new(identifier: Token, value: Node) -> ConstAssignmentNode {
+        ConstAssignmentNode {
+            identifier,
+            value,
+        }
+    }
+}
+
+impl NodeVisit for ConstAssignmentNode {
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.identifier.position
+    }
+
+    fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        let identifier_string = get_name_as_string(self.identifier.clone())?;
+
+        let value = self.value.visit(symbol_table)?;
+
+        symbol_table.set_constant(identifier_string, value, &self.identifier.position)?;
+        Ok(Symbol::new(SymbolType::None, self.get_position()))
+    }
+}
+
+impl std::fmt::Display for ConstAssignmentNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConstAssignmentNode: {:?}", self.identifier.value)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,40 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, Pattern};
+use crate::interpreter::symbols::SymbolType;
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Destructuring assignment node, e.g. `let (q, r) = divmod(a, b);`
+#[derive(Debug, Clone)]
+pub struct DestructuringAssignmentNode {
+    pub pattern: Pattern,
+    pub value: Node,
+}
+
+impl DestructuringAssignmentNode {
+    pub fn new(pattern: Pattern, value: Node) -> DestructuringAssignmentNode {
+        DestructuringAssignmentNode {
+            pattern,
+            value,
+        }
+    }
+}
+
+impl NodeVisit for DestructuringAssignmentNode {
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.pattern.get_position()
+    }
+
+    fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        let value = self.value.visit(symbol_table)?;
+
+        self.pattern.bind(value, symbol_table)?;
+        Ok(Symbol::new(SymbolType::None, self.get_position()))
+    }
+}
+
+impl std::fmt::Display for DestructuringAssignmentNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DestructuringAssignmentNode: {}", self.pattern)
+    }
+}
\ No newline at end of file
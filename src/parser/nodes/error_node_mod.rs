@@ -0,0 +1,38 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit};
+use crate::{interpreter::symbol_table::SymbolTable, lexer::tokens::TokenPosition};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Statement which could not be parsed, left in the tree in place of it
+/// so that the rest of the program can still be parsed
+#[derive(Debug, Clone)]
+pub struct ErrorNode {
+    /// Span of the tokens skipped over
+    pub position: TokenPosition,
+}
+
+impl ErrorNode {
+    pub fn new(position: TokenPosition) -> ErrorNode {
+        ErrorNode { position }
+    }
+}
+
+impl NodeVisit for ErrorNode {
+    fn visit(&self, _symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+        Err(Error::new_parser(
+            "Cannot run a statement with a syntax error".to_string(),
+            &self.position,
+        ))
+    }
+
+    fn get_position(&self) -> TokenPosition {
+        self.position
+    }
+}
+
+impl std::fmt::Display for ErrorNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<error>")
+    }
+}
\ No newline at end of file
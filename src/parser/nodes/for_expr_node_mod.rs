@@ -0,0 +1,68 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, Pattern};
+use crate::interpreter::{symbols::SymbolType, iterators::next};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// For loop expression node, e.g. `for (key, value) in pairs { ... }`
+#[derive(Debug, Clone)]
+pub struct ForExprNode {
+    pub pattern: Pattern,
+    pub iterable: Node,
+    pub body: Node,
+}
+
+impl ForExprNode {
+    pub fn new(pattern: Pattern, iterable: Node, body: Node) -> ForExprNode {
+        ForExprNode {
+            pattern,
+            iterable,
+            body,
+        }
+    }
+}
+
+impl NodeVisit for ForExprNode {
+    fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        let iterable = self.iterable.visit(symbol_table)?;
+
+        let iterator = match iterable.iterate() {
+            Some(iterator) => iterator,
+            None => return Err(Error::new_runtime(
+                ErrorType::TypeError,
+                format!("Cannot iterate over {}", iterable.value.type_name()),
+                &self.iterable.get_position()
+            )),
+        };
+
+        let statements = match self.body {
+            Node::StatementsNode(ref statements) => &statements.statements,
+            _ => panic!("For loop body must be a StatementsNode"),
+        };
+
+        while let Some(item) = next(&iterator, symbol_table)? {
+            self.pattern.bind(item, symbol_table)?;
+
+            for statement in statements {
+                let symbol = statement.visit(symbol_table)?;
+
+                // returning leaves the enclosing function, not just the loop
+                if let Node::ReturnNode(_) = statement {
+                    return Err(Error::new_early_return(symbol, &statement.get_position()));
+                }
+            }
+        }
+
+        Ok(Symbol::new(SymbolType::None, self.get_position()))
+    }
+
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.pattern.get_position()
+    }
+}
+
+impl std::fmt::Display for ForExprNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "for {} in {}", self.pattern, self.iterable)
+    }
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
 #[allow(unused_imports)]
 use super::{Node, NodeVisit, string_parameters, get_name_as_string};
-use crate::{interpreter::{symbol_table::SymbolTable, symbols::SymbolType}, lexer::tokens::TokenPosition};
+use crate::{interpreter::{symbol_table::SymbolTable, symbols::{SymbolType, FunctionSymbol}, iterators::{Generator, IteratorState}}, lexer::tokens::TokenPosition};
 #[allow(unused_imports)]
 use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
 
@@ -9,17 +9,31 @@ use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error,
 pub struct FuncCallNode {
     pub func_node: Node,
     pub args: Vec<Node>,
+    /// Keyword arguments, e.g. `b: 3`
+    pub kwargs: Vec<(Token, Node)>,
 }
 
 impl FuncCallNode {
-    pub fn new(func_node: Node, args: Vec<Node>) -> FuncCallNode {
+    pub fn new(func_node: Node, args: Vec<Node>, kwargs: Vec<(Token, Node)>) -> FuncCallNode {
         FuncCallNode {
             func_node,
             args,
+            kwargs,
         }
     }
 }
 
+/// Evaluate keyword argument nodes into names and symbols
+pub fn evaluate_kwargs(kwargs: &[(Token, Node)], symbol_table: &mut SymbolTable) -> Result<Vec<(String, Symbol)>, Error> {
+    let mut evaluated: Vec<(String, Symbol)> = Vec::new();
+
+    for (name, value) in kwargs {
+        evaluated.push((get_name_as_string(name.clone())?, value.visit(symbol_table)?));
+    }
+
+    Ok(evaluated)
+}
+
 impl NodeVisit for FuncCallNode {
     fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
         // evaluate arguments into symbols
@@ -29,10 +43,7 @@ impl NodeVisit for FuncCallNode {
             args.push(arg.visit(symbol_table)?);
         };
 
-        // create a new symbol table for the function call
-        // arguments will be set after function node is found
-        let mut func_symbol_table = SymbolTable::new(Box::new(&*symbol_table));
-        // println!("func_symbol_table: {:?}", func_symbol_table);
+        let kwargs = evaluate_kwargs(&self.kwargs, symbol_table)?;
 
         // get function node
         let func_identifier = match self.func_node {
@@ -54,70 +65,133 @@ impl NodeVisit for FuncCallNode {
                     &self.func_node.get_position()
                 ))
             },
-            None => return Err(Error::new_runtime(
-                ErrorType::UndefinedVariable, 
+            None => return Err(symbol_table.undefined(
+                &func_identifier,
                 format!("Function {} not found", func_identifier),
                 &self.func_node.get_position()
             ))
         };
 
-        // ensure the right number of arguments are passed
-        if func_node.args.len() != args.len() {
+        func_node.call_with_keywords(args, kwargs, symbol_table, &self.func_node.get_position())
+    }
+
+    fn get_position(&self) -> TokenPosition {
+        self.func_node.get_position()
+    }
+}
+
+impl FunctionSymbol {
+    /// Call the function with already evaluated arguments.
+    /// Used by function call nodes and by built-ins which call back
+    /// into user-defined functions.
+    pub fn call(&self, args: Vec<Symbol>, symbol_table: &SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        self.call_with_keywords(args, Vec::new(), symbol_table, position)
+    }
+
+    /// Call the function with already evaluated positional and keyword arguments
+    pub fn call_with_keywords(&self, args: Vec<Symbol>, kwargs: Vec<(String, Symbol)>, symbol_table: &SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        // functions imported from a module can see the module's names,
+        // and errors inside them are reported in the module's file
+        let result = match self.module {
+            Some(ref module) => {
+                let module_table = SymbolTable::with_symbols(Box::new(symbol_table), module.symbols.clone());
+                self.call_in(args, kwargs, &module_table, position).map_err(|e| e.with_file(&module.file))
+            },
+            None => self.call_in(args, kwargs, symbol_table, position),
+        };
+
+        result.map_err(|e| e.with_call(&self.name, position))
+    }
+
+    /// Evaluate the function body in a new symbol table on top of `symbol_table`
+    fn call_in(&self, args: Vec<Symbol>, kwargs: Vec<(String, Symbol)>, symbol_table: &SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        // create a new symbol table for the function call
+        let mut func_symbol_table = SymbolTable::new(Box::new(symbol_table));
+        func_symbol_table.enter_call(position)?;
+
+        // set arguments in function symbol table
+        let values = func_symbol_table.set_args(&self.parameters, args, kwargs, position)?;
+
+        // generators start running when their first value is asked for
+        if self.generator {
+            let generator = Generator::new(&self.node, func_symbol_table.symbols, self.module.clone(), *position);
+            return Ok(Symbol::new(SymbolType::Iterator(IteratorState::Generator(Box::new(generator)).shared()), *position));
+        }
+
+        let value = self.evaluate(values, &mut func_symbol_table, position)?;
+
+        if !self.returns.accepts(&value) {
+            // values made by built-ins have no position of their own
+            let returned_at = match value.position.is_internal() {
+                true => *position,
+                false => value.position,
+            };
+
             return Err(Error::new_runtime(
-                ErrorType::TypeError, 
-                format!("Expected {} arguments, got {}", func_node.args.len(), args.len()),
-                &self.func_node.get_position()
-            ))
+                ErrorType::TypeError,
+                format!("Function '{}' should return {}, found {}", self.name, self.returns, value.value.type_name()),
+                &returned_at
+            ));
         }
 
-        // set arguments in function symbol table
-        func_symbol_table.set_args(&func_node.args, args);
+        Ok(self.returns.convert(value))
+    }
 
-        // evaluate function symbol
-        let func_symbol = match func_node.node {
+    /// Run the body of the function, giving the value it returns
+    fn evaluate(&self, values: Vec<Symbol>, func_symbol_table: &mut SymbolTable, position: &TokenPosition) -> Result<Symbol, Error> {
+        let func_symbol = match self.node {
             // built in
-            Node::ExecuteBuiltinNode(mut execute_builtin_node) => {
-                execute_builtin_node.args = Some(self.args.clone());
-                execute_builtin_node.visit(&mut func_symbol_table)?
+            Node::ExecuteBuiltinNode(ref execute_builtin_node) => {
+                // variadic arguments are passed after the others
+                let mut builtin_args: Vec<Symbol> = Vec::new();
+                for (parameter, value) in self.parameters.iter().zip(values) {
+                    match value.value {
+                        SymbolType::List(values) if parameter.variadic => builtin_args.extend(values),
+                        _ => builtin_args.push(value),
+                    }
+                }
+
+                let mut execute_builtin_node = execute_builtin_node.clone();
+                execute_builtin_node.args = Some(builtin_args);
+                execute_builtin_node.visit(func_symbol_table)?
             },
 
             // custom function
-            Node::StatementsNode(statements) => {
+            Node::StatementsNode(ref statements) => {
                 // visit all statements until a return statement is found
-                for statement in statements.statements {
-                    match statement {
-                        Node::ReturnNode(ref return_node) => {
-                            return return_node.visit(&mut func_symbol_table);
+                for statement in &statements.statements {
+                    match statement.visit(func_symbol_table) {
+                        Ok(symbol) => if let Node::ReturnNode(_) = statement {
+                            return Ok(symbol);
                         },
-                        _ => {
-                            statement.visit(&mut func_symbol_table)?;
-                        }
+                        // early return from `?`
+                        Err(e) if e.is_early_return() => return Ok(*e.return_value.unwrap()),
+                        Err(e) => return Err(e),
                     }
                 }
 
                 // return none if no return statement
-                Symbol::new(SymbolType::None, self.get_position())
+                Symbol::new(SymbolType::None, *position)
             },
             _ => panic!("Function node must be a StatementsNode")
         };
 
         Ok(func_symbol)
-
-    }
-
-    fn get_position(&self) -> TokenPosition {
-        self.func_node.get_position()
     }
 }
 
-impl ToString for FuncCallNode {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for FuncCallNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut args: Vec<String> = Vec::new();
 
         for arg in &self.args {
             args.push(arg.to_string());
         };
 
-        format!("{}({})", self.func_node.to_string(), args.join(", "))
+        for (name, value) in &self.kwargs {
+            args.push(format!("{}: {}", name, value));
+        };
+
+        write!(f, "{}({})", self.func_node, args.join(", "))
     }
 }
\ No newline at end of file
@@ -1,25 +1,66 @@
-use super::ReturnNode;
+use super::{ReturnNode, Pattern};
 #[allow(unused_imports)]
-use super::{Node, NodeVisit, get_name_as_string, string_parameters};
-use crate::{lexer::tokens::TokenPosition, interpreter::symbols::SymbolType};
+use super::{Node, NodeVisit, get_name_as_string, string_parameters, annotation_type};
+use crate::{lexer::tokens::TokenPosition, interpreter::symbols::{SymbolType, Parameter, ParameterType}};
 #[allow(unused_imports)]
 use crate::{interpreter::symbols::{Symbol, FunctionSymbol}, lexer::tokens::Token, errors::{Error, ErrorType}};
-use crate::interpreter::symbol_table::SymbolTable;
+use crate::interpreter::{symbol_table::SymbolTable, iterators::contains_yield};
 
 /// Function definition node
 #[derive(Debug, Clone)]
 pub struct FuncDefNode {
     pub identifier: Token,
-    pub parameters: Vec<Token>,
+    pub parameters: Vec<ParameterNode>,
     pub body: Option<Node>,
+    /// Name of the declared return type, e.g. `float` in `-> float`
+    pub return_type: Option<Token>,
+}
+
+/// Parameter of a function definition, e.g. `b = 2`, `*rest` or `(x, y)`
+#[derive(Debug, Clone)]
+pub struct ParameterNode {
+    pub identifier: Token,
+    pub default: Option<Node>,
+    pub variadic: bool,
+    /// Pattern the argument is destructured into
+    pub pattern: Option<Pattern>,
+    /// Name of the declared type, e.g. `int` in `w: int`
+    pub annotation: Option<Token>,
 }
 
 impl FuncDefNode {
-    pub fn new(identifier: Token, parameters: Vec<Token>, body: Option<Node>) -> FuncDefNode {
+    pub fn new(identifier: Token, parameters: Vec<ParameterNode>, body: Option<Node>, return_type: Option<Token>) -> FuncDefNode {
         FuncDefNode {
             identifier,
             parameters,
             body,
+            return_type,
+        }
+    }
+}
+
+impl ParameterNode {
+    pub fn new(identifier: Token, default: Option<Node>, variadic: bool, pattern: Option<Pattern>) -> ParameterNode {
+        ParameterNode {
+            identifier,
+            default,
+            variadic,
+            pattern,
+            annotation: None,
+        }
+    }
+
+    /// Declare the type of the parameter
+    pub fn annotated(mut self, annotation: Option<Token>) -> ParameterNode {
+        self.annotation = annotation;
+        self
+    }
+
+    /// Every name bound by the parameter
+    pub fn identifiers(&self) -> Vec<&Token> {
+        match self.pattern {
+            Some(ref pattern) => pattern.identifiers(),
+            None => vec![&self.identifier],
         }
     }
 }
@@ -28,17 +69,44 @@ impl NodeVisit for FuncDefNode {
     fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
         let identifier_string = get_name_as_string(self.identifier.clone())?;
 
+        // default values are evaluated once, when the function is defined
+        let mut parameters: Vec<Parameter> = Vec::new();
+
+        for parameter in &self.parameters {
+            let default = match parameter.default {
+                Some(ref node) => Some(node.visit(symbol_table)?),
+                None => None,
+            };
+
+            let expected = match parameter.annotation {
+                Some(ref annotation) => annotation_type(annotation)?,
+                None => ParameterType::Any,
+            };
+
+            let parameter = match parameter.pattern {
+                Some(ref pattern) => Parameter::new(pattern.to_string(), default, false).destructure(pattern.clone()),
+                None => Parameter::new(get_name_as_string(parameter.identifier.clone())?, default, parameter.variadic),
+            };
+
+            parameters.push(parameter.expected(expected));
+        }
+
+        let returns = match self.return_type {
+            Some(ref annotation) => annotation_type(annotation)?,
+            None => ParameterType::Any,
+        };
+
         // set function in symbol table
         let func_symbol = SymbolType::Function(FunctionSymbol::new(
             identifier_string.clone(), 
-            string_parameters(self.parameters.clone()), 
+            parameters, 
             match self.body {
                 Some(ref node) => node.clone(),
                 None => Node::ReturnNode(Box::new(ReturnNode::new(None)))
             }
-        ));
+        ).generator(self.body.as_ref().is_some_and(contains_yield)).returns(returns));
         
-        symbol_table.set(identifier_string, Symbol::new(func_symbol, self.identifier.position));
+        symbol_table.set(identifier_string, Symbol::new(func_symbol, self.identifier.position), &self.identifier.position)?;
         Ok(Symbol::new(SymbolType::None, self.identifier.position))
     }
     
@@ -47,8 +115,8 @@ impl NodeVisit for FuncDefNode {
     }
 }
 
-impl ToString for FuncDefNode {
-    fn to_string(&self) -> String {
-        format!("FuncDefNode")
+impl std::fmt::Display for FuncDefNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FuncDefNode")
     }
-}
+}
\ No newline at end of file
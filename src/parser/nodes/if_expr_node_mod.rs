@@ -29,14 +29,14 @@ impl NodeVisit for IfExprNode {
             SymbolType::Integer(value) => {
                 if value == 0 {
                     match self.if_false.clone() {
-                        Some(if_false) => return if_false.visit(symbol_table),
-                        None => return Ok(Symbol::new(SymbolType::None, self.get_position())),
+                        Some(if_false) => if_false.visit(symbol_table),
+                        None => Ok(Symbol::new(SymbolType::None, self.get_position())),
                     }
                 } else {
-                    return self.if_true.visit(symbol_table);
+                    self.if_true.visit(symbol_table)
                 }
             }
-            _ => return Err(Error::new_runtime(
+            _ => Err(Error::new_runtime(
                 ErrorType::Exception,
                 "If statement condition must be an integer".to_string(),
                 &self.get_position(),
@@ -49,19 +49,19 @@ impl NodeVisit for IfExprNode {
     }
 }
 
-impl ToString for IfExprNode {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for IfExprNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.if_false.clone() {
-            Some(if_false) => format!(
-                "if {} {{ {} }} else {{ {} }}",
-                self.condition.to_string(),
-                self.if_true.to_string(),
-                if_false.to_string()
+            Some(if_false) => write!(
+                f, "if {} {{ {} }} else {{ {} }}",
+                self.condition,
+                self.if_true,
+                if_false
             ),
-            None => format!(
-                "if {} {{ {} }}",
-                self.condition.to_string(),
-                self.if_true.to_string()
+            None => write!(
+                f, "if {} {{ {} }}",
+                self.condition,
+                self.if_true
             ),
         }
     }
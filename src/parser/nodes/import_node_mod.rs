@@ -0,0 +1,76 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, get_name_as_string};
+use crate::{interpreter::{symbol_table::SymbolTable, symbols::SymbolType, modules::load_module}, lexer::tokens::{TokenPosition, TokenType}};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Import node
+/// `import "utils.yum" as utils;` binds the module's namespace,
+/// `from "utils.yum" import f, g;` binds the given names
+#[derive(Debug, Clone)]
+pub struct ImportNode {
+    pub path: Token,
+    pub alias: Option<Token>,
+    pub names: Vec<Token>,
+}
+
+impl ImportNode {
+    pub fn new(path: Token, alias: Option<Token>, names: Vec<Token>) -> ImportNode {
+        ImportNode {
+            path,
+            alias,
+            names,
+        }
+    }
+}
+
+impl NodeVisit for ImportNode {
+    fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+        let path = match self.path.value {
+            TokenType::String(ref path) => path.clone(),
+            _ => return Err(Error::new_runtime(
+                ErrorType::TypeError, 
+                "Expected module path".to_string(), 
+                &self.path.position
+            ))
+        };
+
+        let module = load_module(symbol_table, &path, &self.get_position())?;
+
+        if self.names.is_empty() {
+            // bind the whole namespace
+            let name = match self.alias {
+                Some(ref alias) => get_name_as_string(alias.clone())?,
+                None => module.name.clone(),
+            };
+
+            symbol_table.set(name, Symbol::new(SymbolType::Module(module), self.get_position()), &self.get_position())?;
+        } else {
+            // bind selected names
+            for name_token in &self.names {
+                let name = get_name_as_string(name_token.clone())?;
+
+                match module.get(&name) {
+                    Some(symbol) => symbol_table.set(name, symbol, &name_token.position)?,
+                    None => return Err(Error::new_runtime(
+                        ErrorType::ImportError, 
+                        format!("Module '{}' has no member '{}'", module.name, name), 
+                        &name_token.position
+                    ).with_suggestions(&name, module.symbols.keys().map(String::as_str)))
+                }
+            }
+        }
+
+        Ok(Symbol::new(SymbolType::None, self.get_position()))
+    }
+
+    fn get_position(&self) -> TokenPosition {
+        self.path.position
+    }
+}
+
+impl std::fmt::Display for ImportNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "import {:?}", self.path.value)
+    }
+}
\ No newline at end of file
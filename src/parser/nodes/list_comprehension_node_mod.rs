@@ -0,0 +1,82 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, Pattern};
+use crate::interpreter::{symbol_table::SymbolTable, symbols::SymbolType, iterators::next};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// List comprehension node, e.g. `[x * 2 for x in xs if x > 0]`
+#[derive(Debug, Clone)]
+pub struct ListComprehensionNode {
+    pub element: Node,
+    pub pattern: Pattern,
+    pub iterable: Node,
+    /// Elements are only kept when the condition is true
+    pub condition: Option<Node>,
+    pub token: Token,
+}
+
+impl ListComprehensionNode {
+    pub fn new(element: Node, pattern: Pattern, iterable: Node, condition: Option<Node>, token: Token) -> ListComprehensionNode {
+        ListComprehensionNode {
+            element,
+            pattern,
+            iterable,
+            condition,
+            token,
+        }
+    }
+}
+
+impl NodeVisit for ListComprehensionNode {
+    fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+        let iterable = self.iterable.visit(symbol_table)?;
+
+        let iterator = match iterable.iterate() {
+            Some(iterator) => iterator,
+            None => return Err(Error::new_runtime(
+                ErrorType::TypeError,
+                format!("Cannot iterate over {}", iterable.value.type_name()),
+                &self.iterable.get_position()
+            )),
+        };
+
+        // names bound by the pattern are not visible after the comprehension
+        let mut comprehension_table = SymbolTable::new(Box::new(symbol_table));
+        let mut elements: Vec<Symbol> = Vec::new();
+
+        while let Some(item) = next(&iterator, &comprehension_table)? {
+            self.pattern.bind(item, &mut comprehension_table)?;
+
+            if let Some(ref condition) = self.condition {
+                let condition_symbol = condition.visit(&mut comprehension_table)?;
+
+                match condition_symbol.value {
+                    SymbolType::Integer(0) => continue,
+                    SymbolType::Integer(_) => {},
+                    _ => return Err(Error::new_runtime(
+                        ErrorType::Exception,
+                        "List comprehension condition must be an integer".to_string(),
+                        &condition.get_position()
+                    )),
+                }
+            }
+
+            elements.push(self.element.visit(&mut comprehension_table)?);
+        }
+
+        Ok(Symbol::new(SymbolType::List(elements), self.get_position()))
+    }
+
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.token.position
+    }
+}
+
+impl std::fmt::Display for ListComprehensionNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.condition {
+            Some(ref condition) => write!(f, "[{} for {} in {} if {}]", self.element, self.pattern, self.iterable, condition),
+            None => write!(f, "[{} for {} in {}]", self.element, self.pattern, self.iterable),
+        }
+    }
+}
\ No newline at end of file
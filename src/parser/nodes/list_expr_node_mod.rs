@@ -1,5 +1,6 @@
 #[allow(unused_imports)]
 use super::{Node, NodeVisit};
+use crate::interpreter::symbols::SymbolType;
 #[allow(unused_imports)]
 use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
 
@@ -7,28 +8,36 @@ use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error,
 #[derive(Debug, Clone)]
 pub struct ListExprNode {
     pub elements: Vec<Node>,
+    pub token: Token,
 }
 
 impl ListExprNode {
-    pub fn new(elements: Vec<Node>) -> ListExprNode {
+    pub fn new(elements: Vec<Node>, token: Token) -> ListExprNode {
         ListExprNode {
             elements,
+            token,
         }
     }
 }
 
 impl NodeVisit for ListExprNode {
-    fn visit(&self, _symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
-        unimplemented!()
+    fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        let mut elements: Vec<Symbol> = Vec::new();
+
+        for element in &self.elements {
+            elements.push(element.visit(symbol_table)?);
+        }
+
+        Ok(Symbol::new(SymbolType::List(elements), self.get_position()))
     }
 
     fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
-        self.elements[0].get_position()
+        self.token.position
     }
 }
 
-impl ToString for ListExprNode {
-    fn to_string(&self) -> String {
-        format!("ListExprNode")
+impl std::fmt::Display for ListExprNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ListExprNode")
     }
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit, get_name_as_string, func_call_node_mod::evaluate_kwargs};
+use crate::{interpreter::symbol_table::SymbolTable, lexer::tokens::TokenPosition};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Method call node, e.g. `value.unwrap_or(0)`
+#[derive(Debug, Clone)]
+pub struct MethodCallNode {
+    pub object: Node,
+    pub method: Token,
+    pub args: Vec<Node>,
+    pub kwargs: Vec<(Token, Node)>,
+}
+
+impl MethodCallNode {
+    pub fn new(object: Node, method: Token, args: Vec<Node>, kwargs: Vec<(Token, Node)>) -> MethodCallNode {
+        MethodCallNode {
+            object,
+            method,
+            args,
+            kwargs,
+        }
+    }
+}
+
+impl NodeVisit for MethodCallNode {
+    fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+        let object = self.object.visit(symbol_table)?;
+
+        // evaluate arguments into symbols
+        let mut args: Vec<Symbol> = Vec::new();
+
+        for arg in &self.args {
+            args.push(arg.visit(symbol_table)?);
+        };
+
+        let kwargs = evaluate_kwargs(&self.kwargs, symbol_table)?;
+
+        let method_name = get_name_as_string(self.method.clone())?;
+        object.call_method(&method_name, args, kwargs, symbol_table, &self.get_position())
+    }
+
+    fn get_position(&self) -> TokenPosition {
+        self.method.position
+    }
+}
+
+impl std::fmt::Display for MethodCallNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut args: Vec<String> = Vec::new();
+
+        for arg in &self.args {
+            args.push(arg.to_string());
+        };
+
+        for (name, value) in &self.kwargs {
+            args.push(format!("{}: {}", name, value));
+        };
+
+        write!(f, "{}.{}({})", self.object, self.method, args.join(", "))
+    }
+}
\ No newline at end of file
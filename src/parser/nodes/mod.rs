@@ -4,39 +4,65 @@
 pub mod number_node_mod;
 pub mod string_node_mod;
 pub mod bin_op_node_mod;
+pub mod compare_node_mod;
 pub mod unary_op_node_mod;
 pub mod var_assignment_node_mod;
+pub mod const_assignment_node_mod;
 pub mod var_arithmetic_assignment_node_mod;
 pub mod var_access_node_mod;
 pub mod if_expr_node_mod;
 pub mod func_def_node_mod;
 pub mod func_call_node_mod;
 pub mod list_expr_node_mod;
+pub mod list_comprehension_node_mod;
 pub mod statements_node_mod;
 pub mod return_node_mod;
+pub mod yield_node_mod;
 pub mod assert_node_mod;
 pub mod execute_builtin_mod;
+pub mod try_node_mod;
+pub mod method_call_node_mod;
+pub mod attribute_access_node_mod;
+pub mod import_node_mod;
+pub mod tuple_expr_node_mod;
+pub mod destructuring_assignment_node_mod;
+pub mod for_expr_node_mod;
+pub mod error_node_mod;
+pub mod pattern_mod;
 
 pub use number_node_mod::NumberNode;
 pub use string_node_mod::StringNode;
 pub use bin_op_node_mod::BinOpNode;
+pub use compare_node_mod::{CompareNode, Comparison};
 pub use unary_op_node_mod::UnaryOpNode;
 pub use var_assignment_node_mod::VarAssignmentNode;
+pub use const_assignment_node_mod::ConstAssignmentNode;
 pub use var_arithmetic_assignment_node_mod::VarArithmeticAssignmentNode;
 pub use var_access_node_mod::VarAccessNode;
 pub use if_expr_node_mod::IfExprNode;
 pub use func_def_node_mod::FuncDefNode;
 pub use func_call_node_mod::FuncCallNode;
 pub use list_expr_node_mod::ListExprNode;
+pub use list_comprehension_node_mod::ListComprehensionNode;
 pub use statements_node_mod::StatementsNode;
 pub use return_node_mod::ReturnNode;
+pub use yield_node_mod::YieldNode;
 pub use assert_node_mod::AssertNode;
 pub use execute_builtin_mod::ExecuteBuiltinNode;
+pub use try_node_mod::TryNode;
+pub use method_call_node_mod::MethodCallNode;
+pub use attribute_access_node_mod::AttributeAccessNode;
+pub use import_node_mod::ImportNode;
+pub use tuple_expr_node_mod::TupleExprNode;
+pub use destructuring_assignment_node_mod::DestructuringAssignmentNode;
+pub use for_expr_node_mod::ForExprNode;
+pub use error_node_mod::ErrorNode;
+pub use pattern_mod::Pattern;
 
 use crate::{
     errors::{Error, ErrorType}, 
     interpreter::{
-        symbols::Symbol,
+        symbols::{Symbol, ParameterType},
         symbol_table::SymbolTable,
     }, lexer::tokens::{TokenPosition, TokenType, Token},
 };
@@ -47,18 +73,30 @@ pub enum Node {
     NumberNode(Box<NumberNode>),
     StringNode(Box<StringNode>), 
     BinOpNode(Box<BinOpNode>),
+    CompareNode(Box<CompareNode>),
     UnaryOpNode(Box<UnaryOpNode>),
     VarAssignmentNode(Box<VarAssignmentNode>),
+    ConstAssignmentNode(Box<ConstAssignmentNode>),
     VarArithmeticAssignmentNode(Box<VarArithmeticAssignmentNode>),
     VarAccessNode(Box<VarAccessNode>),
     IfExprNode(Box<IfExprNode>),
     FuncDefNode(Box<FuncDefNode>),
     FuncCallNode(Box<FuncCallNode>),
     ListExprNode(Box<ListExprNode>),
+    ListComprehensionNode(Box<ListComprehensionNode>),
     StatementsNode(Box<StatementsNode>),
     ReturnNode(Box<ReturnNode>),
+    YieldNode(Box<YieldNode>),
     AssertNode(Box<AssertNode>),
     ExecuteBuiltinNode(Box<ExecuteBuiltinNode>),
+    TryNode(Box<TryNode>),
+    MethodCallNode(Box<MethodCallNode>),
+    AttributeAccessNode(Box<AttributeAccessNode>),
+    ImportNode(Box<ImportNode>),
+    TupleExprNode(Box<TupleExprNode>),
+    DestructuringAssignmentNode(Box<DestructuringAssignmentNode>),
+    ForExprNode(Box<ForExprNode>),
+    ErrorNode(Box<ErrorNode>),
 }
 
 /// Trait for the node to be able to be visited (evaluated)
@@ -79,6 +117,18 @@ fn get_name_as_string(identifier: Token) -> Result<String, Error> {
     }
 }
 
+/// Type named by an annotation, e.g. `int` in `w: int`
+fn annotation_type(annotation: &Token) -> Result<ParameterType, Error> {
+    match ParameterType::from_annotation(&annotation.to_string()) {
+        Some(expected) => Ok(expected),
+        None => Err(Error::new_runtime(
+            ErrorType::TypeError,
+            format!("Unknown type '{}'", annotation),
+            &annotation.position
+        )),
+    }
+}
+
 /// Convert a vector of string tokens as strings
 fn string_parameters(parameters: Vec<Token>) -> Vec<String> {
     let mut strings: Vec<String> = Vec::new();
@@ -96,17 +146,29 @@ impl NodeVisit for Node {
             Node::NumberNode(node) => node.visit(symbol_table),
             Node::StringNode(node) => node.visit(symbol_table),
             Node::BinOpNode(node) => node.visit(symbol_table),
+            Node::CompareNode(node) => node.visit(symbol_table),
             Node::UnaryOpNode(node) => node.visit(symbol_table),
             Node::VarAssignmentNode(node) => node.visit(symbol_table),
+            Node::ConstAssignmentNode(node) => node.visit(symbol_table),
             Node::VarArithmeticAssignmentNode(node) => node.visit(symbol_table),
             Node::VarAccessNode(node) => node.visit(symbol_table),
             Node::IfExprNode(node) => node.visit(symbol_table),
             Node::FuncDefNode(node) => node.visit(symbol_table),
             Node::FuncCallNode(node) => node.visit(symbol_table),
             Node::ListExprNode(node) => node.visit(symbol_table),
+            Node::ListComprehensionNode(node) => node.visit(symbol_table),
             Node::ReturnNode(node) => node.visit(symbol_table),
+            Node::YieldNode(node) => node.visit(symbol_table),
             Node::AssertNode(node) => node.visit(symbol_table),
             Node::ExecuteBuiltinNode(node) => node.visit(symbol_table),
+            Node::TryNode(node) => node.visit(symbol_table),
+            Node::MethodCallNode(node) => node.visit(symbol_table),
+            Node::AttributeAccessNode(node) => node.visit(symbol_table),
+            Node::ImportNode(node) => node.visit(symbol_table),
+            Node::TupleExprNode(node) => node.visit(symbol_table),
+            Node::DestructuringAssignmentNode(node) => node.visit(symbol_table),
+            Node::ForExprNode(node) => node.visit(symbol_table),
+            Node::ErrorNode(node) => node.visit(symbol_table),
             Node::StatementsNode(_) => panic!("Cannot visit statements node"),
         }
     }
@@ -116,39 +178,83 @@ impl NodeVisit for Node {
             Node::NumberNode(node) => node.get_position(),
             Node::StringNode(node) => node.get_position(),
             Node::BinOpNode(node) => node.get_position(),
+            Node::CompareNode(node) => node.get_position(),
             Node::UnaryOpNode(node) => node.get_position(),
             Node::VarAssignmentNode(node) => node.get_position(),
+            Node::ConstAssignmentNode(node) => node.get_position(),
             Node::VarArithmeticAssignmentNode(node) => node.get_position(),
             Node::VarAccessNode(node) => node.get_position(),
             Node::IfExprNode(node) => node.get_position(),
             Node::FuncDefNode(node) => node.get_position(),
             Node::FuncCallNode(node) => node.get_position(),
             Node::ListExprNode(node) => node.get_position(),
+            Node::ListComprehensionNode(node) => node.get_position(),
             Node::ReturnNode(node) => node.get_position(),
+            Node::YieldNode(node) => node.get_position(),
             Node::AssertNode(node) => node.get_position(),
             Node::ExecuteBuiltinNode(node) => node.get_position(),
+            Node::TryNode(node) => node.get_position(),
+            Node::MethodCallNode(node) => node.get_position(),
+            Node::AttributeAccessNode(node) => node.get_position(),
+            Node::ImportNode(node) => node.get_position(),
+            Node::TupleExprNode(node) => node.get_position(),
+            Node::DestructuringAssignmentNode(node) => node.get_position(),
+            Node::ForExprNode(node) => node.get_position(),
+            Node::ErrorNode(node) => node.get_position(),
             Node::StatementsNode(_) => panic!("Cannot visit statements node"),
         }
     }
 }
 
+impl Node {
+    /// Position spanning the whole expression, from its first token
+    /// to its last, for underlining it in errors
+    pub fn span(&self) -> TokenPosition {
+        match self {
+            Node::BinOpNode(node) => node.left_node.span().to(&node.right_node.span()),
+            Node::CompareNode(node) => match node.comparisons.last() {
+                Some(last) => node.first.span().to(&last.operand.span()),
+                None => node.first.span(),
+            },
+            Node::UnaryOpNode(node) => node.token.position.to(&node.right.span()),
+            Node::FuncCallNode(node) => match node.args.last() {
+                Some(last) => node.func_node.span().to(&last.span()),
+                None => node.func_node.span(),
+            },
+            node => node.get_position(),
+        }
+    }
+}
+
 impl std::fmt::Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let text = match self {
             Node::NumberNode(node) => node.to_string(),
             Node::StringNode(node) => node.to_string(),
             Node::BinOpNode(node) => node.to_string(),
+            Node::CompareNode(node) => node.to_string(),
             Node::UnaryOpNode(node) => node.to_string(),
             Node::VarAssignmentNode(node) => node.to_string(),
+            Node::ConstAssignmentNode(node) => node.to_string(),
             Node::VarArithmeticAssignmentNode(node) => node.to_string(),
             Node::VarAccessNode(node) => node.to_string(),
             Node::IfExprNode(node) => node.to_string(),
             Node::FuncDefNode(node) => node.to_string(),
             Node::FuncCallNode(node) => node.to_string(),
             Node::ListExprNode(node) => node.to_string(),
+            Node::ListComprehensionNode(node) => node.to_string(),
             Node::ReturnNode(node) => node.to_string(),
+            Node::YieldNode(node) => node.to_string(),
             Node::AssertNode(node) => node.to_string(),
             Node::ExecuteBuiltinNode(node) => node.to_string(),
+            Node::TryNode(node) => node.to_string(),
+            Node::MethodCallNode(node) => node.to_string(),
+            Node::AttributeAccessNode(node) => node.to_string(),
+            Node::ImportNode(node) => node.to_string(),
+            Node::TupleExprNode(node) => node.to_string(),
+            Node::DestructuringAssignmentNode(node) => node.to_string(),
+            Node::ForExprNode(node) => node.to_string(),
+            Node::ErrorNode(node) => node.to_string(),
             Node::StatementsNode(_) => panic!("Cannot stringify statements node"),
         };
 
@@ -34,8 +34,8 @@ impl NodeVisit for NumberNode {
     }
 }
 
-impl ToString for NumberNode {
-    fn to_string(&self) -> String {
-        format!("{}", self.token.value)
+impl std::fmt::Display for NumberNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token.value)
     }
 }
\ No newline at end of file
@@ -0,0 +1,82 @@
+use super::get_name_as_string;
+use crate::{interpreter::{symbol_table::SymbolTable, symbols::{Symbol, SymbolType}}, lexer::tokens::{Token, TokenPosition}};
+#[allow(unused_imports)]
+use crate::errors::{self, Error, ErrorType};
+
+/// Pattern which a value is destructured into,
+/// e.g. `q`, `(q, r)` or `[first, second]`
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Identifier(Token),
+    Tuple(Vec<Pattern>, Token),
+    List(Vec<Pattern>, Token),
+}
+
+impl Pattern {
+    /// Bind a value to every name in the pattern
+    pub fn bind(&self, value: Symbol, symbol_table: &mut SymbolTable) -> Result<(), Error> {
+        let (patterns, values) = match (self, value.value) {
+            (Pattern::Identifier(identifier), value_type) => {
+                let name = get_name_as_string(identifier.clone())?;
+                symbol_table.set(name, Symbol::new(value_type, value.position), &identifier.position)?;
+                return Ok(());
+            },
+            (Pattern::Tuple(patterns, _), SymbolType::Tuple(values)) => (patterns, values),
+            (Pattern::List(patterns, _), SymbolType::List(values)) => (patterns, values),
+            (_, value_type) => return Err(Error::new_runtime(
+                ErrorType::TypeError,
+                format!("Cannot destructure {} into {}", value_type.type_name(), self),
+                &self.get_position()
+            )),
+        };
+
+        if patterns.len() != values.len() {
+            return Err(Error::new_runtime(
+                ErrorType::ArgumentError,
+                format!("Expected {} to destructure into {}, got {}", errors::count(patterns.len(), "value"), self, values.len()),
+                &self.get_position()
+            ));
+        }
+
+        for (pattern, value) in patterns.iter().zip(values) {
+            pattern.bind(value, symbol_table)?;
+        }
+
+        Ok(())
+    }
+
+    /// Every name bound by the pattern
+    pub fn identifiers(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Identifier(token) => vec![token],
+            Pattern::Tuple(patterns, _) | Pattern::List(patterns, _) => patterns.iter().flat_map(|x| x.identifiers()).collect(),
+        }
+    }
+
+    /// Identifier or opening bracket of the pattern
+    pub fn token(&self) -> &Token {
+        match self {
+            Pattern::Identifier(token) | Pattern::Tuple(_, token) | Pattern::List(_, token) => token,
+        }
+    }
+
+    pub fn get_position(&self) -> TokenPosition {
+        self.token().position
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Identifier(token) => write!(f, "{}", token),
+            Pattern::Tuple(patterns, _) => {
+                let patterns: Vec<String> = patterns.iter().map(|x| x.to_string()).collect();
+                write!(f, "({})", patterns.join(", "))
+            },
+            Pattern::List(patterns, _) => {
+                let patterns: Vec<String> = patterns.iter().map(|x| x.to_string()).collect();
+                write!(f, "[{}]", patterns.join(", "))
+            },
+        }
+    }
+}
\ No newline at end of file
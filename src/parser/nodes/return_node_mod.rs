@@ -13,7 +13,7 @@ pub struct ReturnNode {
 impl ReturnNode {
     pub fn new(value: Option<Node>) -> ReturnNode {
         ReturnNode {
-            value: value,
+            value,
         }
     }
 }
@@ -31,8 +31,8 @@ impl NodeVisit for ReturnNode {
     }
 }
 
-impl ToString for ReturnNode {
-    fn to_string(&self) -> String {
-        format!("ReturnNode")
+impl std::fmt::Display for ReturnNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReturnNode")
     }
 }
\ No newline at end of file
@@ -16,8 +16,8 @@ impl StatementsNode {
     }
 }
 
-impl ToString for StatementsNode {
-    fn to_string(&self) -> String {
-        format!("StatementsNode")
+impl std::fmt::Display for StatementsNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StatementsNode")
     }
 }
\ No newline at end of file
@@ -33,8 +33,8 @@ impl NodeVisit for StringNode {
     }
 }
 
-impl ToString for StringNode {
-    fn to_string(&self) -> String {
-        format!("{}", self.token.value)
+impl std::fmt::Display for StringNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token.value)
     }
 }
\ No newline at end of file
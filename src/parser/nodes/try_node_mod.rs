@@ -0,0 +1,48 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit};
+use crate::{interpreter::{symbol_table::SymbolTable, symbols::SymbolType}, lexer::tokens::TokenPosition};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Postfix `?` node
+/// Unwraps Ok/Some values, and returns Err/None from the enclosing function
+#[derive(Debug, Clone)]
+pub struct TryNode {
+    pub value: Node,
+    pub token: Token,
+}
+
+impl TryNode {
+    pub fn new(value: Node, token: Token) -> TryNode {
+        TryNode {
+            value,
+            token,
+        }
+    }
+}
+
+impl NodeVisit for TryNode {
+    fn visit(&self, symbol_table: &mut SymbolTable) -> Result<Symbol, Error> {
+        let symbol = self.value.visit(symbol_table)?;
+
+        match symbol.value {
+            SymbolType::Ok(value) | SymbolType::Some(value) => Ok(*value),
+            SymbolType::Err(_) | SymbolType::None => Err(Error::new_early_return(symbol, &self.get_position())),
+            _ => Err(Error::new_runtime(
+                ErrorType::TypeError, 
+                format!("Cannot apply '?' to {}, expected a result or option", symbol.value),
+                &self.get_position()
+            ))
+        }
+    }
+
+    fn get_position(&self) -> TokenPosition {
+        self.token.position
+    }
+}
+
+impl std::fmt::Display for TryNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}?", self.value)
+    }
+}
\ No newline at end of file
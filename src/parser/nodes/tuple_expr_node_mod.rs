@@ -0,0 +1,43 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit};
+use crate::interpreter::symbols::SymbolType;
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Tuple expression node, e.g. `(a, b)`
+#[derive(Debug, Clone)]
+pub struct TupleExprNode {
+    pub elements: Vec<Node>,
+    pub token: Token,
+}
+
+impl TupleExprNode {
+    pub fn new(elements: Vec<Node>, token: Token) -> TupleExprNode {
+        TupleExprNode {
+            elements,
+            token,
+        }
+    }
+}
+
+impl NodeVisit for TupleExprNode {
+    fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        let mut elements: Vec<Symbol> = Vec::new();
+
+        for element in &self.elements {
+            elements.push(element.visit(symbol_table)?);
+        }
+
+        Ok(Symbol::new(SymbolType::Tuple(elements), self.get_position()))
+    }
+
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.token.position
+    }
+}
+
+impl std::fmt::Display for TupleExprNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TupleExprNode")
+    }
+}
\ No newline at end of file
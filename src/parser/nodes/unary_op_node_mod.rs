@@ -15,7 +15,7 @@ impl UnaryOpNode {
     pub fn new(token: Token, right: Node) -> UnaryOpNode {
         UnaryOpNode {
             token,
-            right: right,
+            right,
         }
     }
 }
@@ -43,8 +43,8 @@ impl NodeVisit for UnaryOpNode {
     }
 }
 
-impl ToString for UnaryOpNode {
-    fn to_string(&self) -> String {
-        format!("{} {}", self.token.value, self.right.to_string())
+impl std::fmt::Display for UnaryOpNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.token.value, self.right)
     }
 }
\ No newline at end of file
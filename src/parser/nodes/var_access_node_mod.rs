@@ -17,11 +17,13 @@ impl VarAccessNode {
 
 impl NodeVisit for VarAccessNode {
     fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
-        match symbol_table.get(&get_name_as_string(self.identifier.clone())?) {
+        let name = get_name_as_string(self.identifier.clone())?;
+
+        match symbol_table.get(&name) {
             Some(symbol) => Ok(symbol.clone()),
-            None => Err(Error::new_runtime(
-                ErrorType::UndefinedVariable, 
-                format!("Undefined variable '{:?}'", self.identifier.value), 
+            None => Err(symbol_table.undefined(
+                &name,
+                format!("Undefined variable '{}'", name), 
                 &self.identifier.position
             ))
         }
@@ -32,8 +34,8 @@ impl NodeVisit for VarAccessNode {
     }
 }
 
-impl ToString for VarAccessNode {
-    fn to_string(&self) -> String {
-        format!("VarAccessNode: {:?}", self.identifier.value)
+impl std::fmt::Display for VarAccessNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VarAccessNode: {:?}", self.identifier.value)
     }
 }
\ No newline at end of file
@@ -34,23 +34,23 @@ impl NodeVisit for VarArithmeticAssignmentNode {
         let identifier_string = get_name_as_string(self.identifier.clone())?;
         let left_symbol = match symbol_table.get(&identifier_string) {
             Some(symbol) => symbol.clone(),
-            None => return Err(Error::new_runtime(
-                ErrorType::UndefinedVariable, 
-                format!("Undefined variable '{:?}'", self.identifier.value), 
+            None => return Err(symbol_table.undefined(
+                &identifier_string,
+                format!("Undefined variable '{}'", identifier_string), 
                 &self.identifier.position
             )),
         };
 
         // perform the operation and assign
-        symbol_table.set(identifier_string, left_symbol.add(&right_symbol)?);
+        symbol_table.set(identifier_string, left_symbol.binary_operation(&self.op_token, &right_symbol)?, &self.identifier.position)?;
         Ok(Symbol::new(SymbolType::None, self.get_position()))
     }
 }
 
-impl ToString for VarArithmeticAssignmentNode {
-    fn to_string(&self) -> String {
-        format!(
-            "VarArithmeticAssignmentNode: {:?} {:?} {:?}",
+impl std::fmt::Display for VarArithmeticAssignmentNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "VarArithmeticAssignmentNode: {:?} {:?} {:?}",
             self.identifier.value, self.op_token.value, self.value.to_string()
         )
     }
@@ -1,5 +1,5 @@
 #[allow(unused_imports)]
-use super::{Node, NodeVisit, get_name_as_string};
+use super::{Node, NodeVisit, get_name_as_string, annotation_type};
 use crate::interpreter::symbols::SymbolType;
 #[allow(unused_imports)]
 use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
@@ -9,13 +9,16 @@ use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error,
 pub struct VarAssignmentNode {
     pub identifier: Token,
     pub value: Node,
+    /// Name of the declared type, e.g. `string` in `let name: string = ...`
+    pub annotation: Option<Token>,
 }
 
 impl VarAssignmentNode {
-    pub fn new(identifier: Token, value: Node) -> VarAssignmentNode {
+    pub fn new(identifier: Token, value: Node, annotation: Option<Token>) -> VarAssignmentNode {
         VarAssignmentNode {
             identifier,
             value,
+            annotation,
         }
     }
 }
@@ -28,15 +31,29 @@ impl NodeVisit for VarAssignmentNode {
     fn visit(&self, symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
         let identifier_string = get_name_as_string(self.identifier.clone())?;
 
-        let value = self.value.visit(symbol_table)?;
+        let mut value = self.value.visit(symbol_table)?;
 
-        symbol_table.set(identifier_string, value);
+        if let Some(ref annotation) = self.annotation {
+            let declared = annotation_type(annotation)?;
+
+            if !declared.accepts(&value) {
+                return Err(Error::new_runtime(
+                    ErrorType::TypeError,
+                    format!("Cannot assign {} to '{}' declared as {}", value.value.type_name(), identifier_string, declared),
+                    &self.value.span()
+                ));
+            }
+
+            value = declared.convert(value);
+        }
+
+        symbol_table.set(identifier_string, value, &self.identifier.position)?;
         Ok(Symbol::new(SymbolType::None, self.get_position()))
     }
 }
 
-impl ToString for VarAssignmentNode {
-    fn to_string(&self) -> String {
-        format!("VarAssignmentNode: {:?}", self.identifier.value)
+impl std::fmt::Display for VarAssignmentNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VarAssignmentNode: {:?}", self.identifier.value)
     }
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+#[allow(unused_imports)]
+use super::{Node, NodeVisit};
+#[allow(unused_imports)]
+use crate::{interpreter::symbols::Symbol, lexer::tokens::Token, errors::{Error, ErrorType}};
+
+/// Yield statement node, producing the next value of a generator.
+/// Generators run their own statements, so a yield is only visited
+/// directly when it is outside of a function.
+#[derive(Debug, Clone)]
+pub struct YieldNode {
+    pub value: Node,
+    pub token: Token,
+}
+
+impl YieldNode {
+    pub fn new(value: Node, token: Token) -> YieldNode {
+        YieldNode {
+            value,
+            token,
+        }
+    }
+}
+
+impl NodeVisit for YieldNode {
+    fn visit(&self, _symbol_table: &mut crate::interpreter::symbol_table::SymbolTable) -> Result<Symbol, Error> {
+        Err(Error::new_runtime(
+            ErrorType::Exception,
+            "Cannot yield outside of a function".to_string(),
+            &self.get_position()
+        ))
+    }
+
+    fn get_position(&self) -> crate::lexer::tokens::TokenPosition {
+        self.token.position
+    }
+}
+
+impl std::fmt::Display for YieldNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "yield {}", self.value)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,194 @@
+//! Interactive prompt which runs statements as they are entered.
+//!
+//! One interpreter is kept for the whole session, so variables, functions
+//! and imports defined by earlier inputs can be used by later ones, even
+//! after an input fails. Input continues over several lines until its
+//! brackets and strings are closed and it ends with `;`, and an empty line runs
+//! whatever has been entered so far.
+//!
+//! Names and types are checked as each statement runs, rather than
+//! before, since a statement may use names defined by earlier inputs.
+
+use std::io::IsTerminal;
+
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::{
+    diagnostics,
+    errors::{Error, SourceFile},
+    interpreter::{self, symbols::SymbolType, Interpreter},
+    lexer::{self, tokens::TokenType, Lexer},
+    parser::{nodes::Node, Parser},
+};
+
+/// File the history of entered lines is kept in, within the current directory
+const HISTORY_FILE: &str = ".yum_history";
+
+const PROMPT: &str = "yum> ";
+const CONTINUATION_PROMPT: &str = "...  ";
+
+/// Run the prompt until the input is closed with Ctrl-D
+pub fn run() {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("Error while starting the prompt: {}", e);
+            return;
+        }
+    };
+
+    // there is no history the first time the prompt is used
+    let _ = editor.load_history(HISTORY_FILE);
+
+    let mut interpreter = Interpreter::new();
+    let mut input = String::new();
+
+    println!("Yum {}. Press Ctrl-D to exit", env!("CARGO_PKG_VERSION"));
+
+    loop {
+        let prompt = match input.is_empty() {
+            true => PROMPT,
+            false => CONTINUATION_PROMPT,
+        };
+
+        match editor.readline(prompt) {
+            Ok(line) => {
+                let _ = editor.add_history_entry(line.as_str());
+
+                // an empty line finishes the input even if it is incomplete
+                if !line.trim().is_empty() {
+                    input.push_str(&line);
+                    input.push('\n');
+
+                    if !is_complete(&input) {
+                        continue;
+                    }
+                }
+
+                match evaluate(&input, &mut interpreter) {
+                    Ok(_) => {},
+                    // `exit` ends the session
+                    Err(errors) if errors[0].is_exit() => {
+                        save_history(&mut editor);
+                        std::process::exit(errors[0].exit_code());
+                    },
+                    Err(errors) => {
+                        let file = SourceFile::new("<repl>", &input);
+                        let errors: Vec<Error> = errors.into_iter().map(|e| e.with_file(&file)).collect();
+                        let colour = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+                        eprintln!("{}", diagnostics::render_all(&errors, colour));
+                    },
+                }
+
+                input.clear();
+            },
+            // Ctrl-C abandons the current input
+            Err(ReadlineError::Interrupted) => input.clear(),
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Error while reading input: {}", e);
+                break;
+            },
+        }
+    }
+
+    save_history(&mut editor);
+}
+
+fn save_history(editor: &mut DefaultEditor) {
+    if let Err(e) = editor.save_history(HISTORY_FILE) {
+        eprintln!("Error while saving history: {}", e);
+    }
+}
+
+/// Whether input can be run, as its brackets are all closed and it ends
+/// with `;`. Input which cannot be lexed is complete, so that its error
+/// is shown rather than waiting for more lines, unless it ends inside a
+/// string, which may continue on the next line.
+pub fn is_complete(input: &str) -> bool {
+    let tokens = match Lexer::new(input.to_string()).make_tokens() {
+        Ok(tokens) => tokens,
+        Err(e) => return e.error_message != lexer::UNTERMINATED_STRING,
+    };
+
+    let mut depth = 0;
+    let mut last = None;
+
+    for token in &tokens {
+        match token.value {
+            TokenType::LeftParen | TokenType::LeftSquare | TokenType::LeftBrace => depth += 1,
+            TokenType::RightParen | TokenType::RightSquare | TokenType::RightBrace => depth -= 1,
+            TokenType::EOF => continue,
+            _ => {},
+        }
+
+        last = Some(&token.value);
+    }
+
+    depth <= 0 && matches!(last, Some(TokenType::Semicolon))
+}
+
+/// Run each statement of the input, printing the value of expressions.
+/// Gives every syntax error in the input, or the error which stopped it running.
+fn evaluate(input: &str, interpreter: &mut Interpreter) -> Result<(), Vec<Error>> {
+    if input.trim().is_empty() {
+        return Ok(());
+    }
+
+    let tokens = Lexer::new(input.to_string()).make_tokens().map_err(|e| vec![e])?;
+    let ast = Parser::new(tokens).parse()?;
+
+    let statements = match ast {
+        Node::StatementsNode(statements) => statements.statements,
+        statement => vec![statement],
+    };
+
+    for statement in statements {
+        let value = interpreter::run_statement(&statement, &mut interpreter.symbol_table).map_err(|e| vec![e])?;
+
+        if is_expression(&statement) && !matches!(value.value, SymbolType::None) {
+            println!("{}", value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a statement is an expression whose value should be shown,
+/// rather than a definition or assignment
+fn is_expression(statement: &Node) -> bool {
+    !matches!(
+        statement,
+        Node::VarAssignmentNode(_)
+            | Node::ConstAssignmentNode(_)
+            | Node::VarArithmeticAssignmentNode(_)
+            | Node::DestructuringAssignmentNode(_)
+            | Node::FuncDefNode(_)
+            | Node::ImportNode(_)
+            | Node::AssertNode(_)
+            | Node::ForExprNode(_)
+    )
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_continues_until_brackets_close_and_it_ends_with_a_semicolon() {
+        assert!(is_complete("print(\"hi\");\n"));
+        assert!(!is_complete("func f() {\n"));
+        assert!(!is_complete("let a = 1\n"));
+        assert!(is_complete("func f() {\n    return 1;\n};\n"));
+    }
+
+    #[test]
+    fn input_continues_inside_a_string() {
+        assert!(!is_complete("print(\"first line\n"));
+        assert!(is_complete("print(\"first line\nsecond line\");\n"));
+    }
+
+    #[test]
+    fn input_which_cannot_be_lexed_is_complete() {
+        assert!(is_complete("let a = 1 $ 2\n"));
+    }
+}
@@ -0,0 +1,399 @@
+//! Resolves the names used by an AST before it is interpreted.
+//! Every scope (globals, function parameters and bodies) is walked so that
+//! undefined names, calls with the wrong number of arguments and keyword
+//! arguments which do not match a parameter are reported
+//! up front, instead of only when a branch happens to run.
+//!
+//! Function bodies are resolved after the rest of their enclosing scope, so
+//! they can use names which are defined after the function itself.
+
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    errors::{count, Error, ErrorType},
+    interpreter::{modules::module_name, symbol_table::SymbolTable, symbols::{Parameter, SymbolType}},
+    lexer::tokens::{Keyword, Token, TokenPosition, TokenType},
+    parser::nodes::{FuncDefNode, Node, Pattern, func_def_node_mod::ParameterNode},
+};
+
+/// What a name is bound to
+#[derive(Debug, Clone)]
+enum Binding {
+    Variable,
+    /// Function with known parameters
+    Function(Rc<[Argument]>),
+}
+
+/// Parameter of a function, as far as calls to it are concerned
+#[derive(Debug, Clone)]
+struct Argument {
+    name: String,
+    required: bool,
+    variadic: bool,
+}
+
+/// Walks the scopes of an AST, collecting errors
+pub struct Resolver<'a> {
+    scopes: Vec<HashMap<String, Binding>>,
+    errors: Vec<Error>,
+    /// Functions whose bodies are resolved at the end of the current scope
+    deferred: Vec<&'a FuncDefNode>,
+}
+
+/// Resolve a program, returning every error found in source order
+pub fn resolve(ast: &Node) -> Result<(), Vec<Error>> {
+    let mut resolver = Resolver::new();
+    resolver.resolve_body(ast);
+
+    let mut errors = resolver.errors;
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        errors.sort_by_key(|e| (e.position.line, e.position.column));
+        Err(errors)
+    }
+}
+
+impl<'a> Resolver<'a> {
+    /// Create a resolver whose outermost scope contains the built-ins
+    pub fn new() -> Resolver<'a> {
+        let mut builtins: HashMap<String, Binding> = HashMap::new();
+
+        for (name, symbol) in SymbolTable::new_global().symbols {
+            let binding = match symbol.value {
+                SymbolType::Function(ref function) => arity(&function.parameters),
+                _ => Binding::Variable,
+            };
+
+            builtins.insert(name, binding);
+        }
+
+        Resolver {
+            scopes: vec![builtins],
+            errors: Vec::new(),
+            deferred: Vec::new(),
+        }
+    }
+
+    /// Resolve the statements of a program or function body in a new scope.
+    /// Functions defined in it are resolved once all its statements have been.
+    fn resolve_body(&mut self, body: &'a Node) {
+        let outer_deferred = std::mem::take(&mut self.deferred);
+        self.scopes.push(HashMap::new());
+
+        if let Node::StatementsNode(ref statements) = body {
+            for statement in &statements.statements {
+                self.resolve_node(statement);
+            }
+        }
+
+        while let Some(func_def) = self.deferred.pop() {
+            self.resolve_function(func_def);
+        }
+
+        self.scopes.pop();
+        self.deferred = outer_deferred;
+    }
+
+    /// Resolve the body of a function with its parameters in scope
+    fn resolve_function(&mut self, func_def: &'a FuncDefNode) {
+        self.scopes.push(HashMap::new());
+
+        for parameter in &func_def.parameters {
+            for identifier in parameter.identifiers() {
+                self.declare(identifier, Binding::Variable);
+            }
+        }
+
+        if let Some(ref body) = func_def.body {
+            self.resolve_body(body);
+        }
+
+        self.scopes.pop();
+    }
+
+    fn resolve_node(&mut self, node: &'a Node) {
+        match node {
+            Node::NumberNode(_) | Node::StringNode(_) | Node::ExecuteBuiltinNode(_) | Node::ErrorNode(_) => {},
+            Node::BinOpNode(node) => {
+                self.resolve_node(&node.left_node);
+                self.resolve_node(&node.right_node);
+            },
+            Node::CompareNode(node) => {
+                self.resolve_node(&node.first);
+                for comparison in &node.comparisons {
+                    self.resolve_node(&comparison.operand);
+                }
+            },
+            Node::UnaryOpNode(node) => self.resolve_node(&node.right),
+            Node::VarAssignmentNode(node) => {
+                self.resolve_node(&node.value);
+                self.declare(&node.identifier, Binding::Variable);
+            },
+            Node::ConstAssignmentNode(node) => {
+                self.resolve_node(&node.value);
+                self.declare(&node.identifier, Binding::Variable);
+            },
+            Node::VarArithmeticAssignmentNode(node) => {
+                self.resolve_node(&node.value);
+                self.lookup(&node.identifier);
+            },
+            Node::DestructuringAssignmentNode(node) => {
+                self.resolve_node(&node.value);
+                self.declare_pattern(&node.pattern);
+            },
+            Node::VarAccessNode(node) => { self.lookup(&node.identifier); },
+            Node::IfExprNode(node) => {
+                self.resolve_node(&node.condition);
+                self.resolve_node(&node.if_true);
+                if let Some(ref if_false) = node.if_false {
+                    self.resolve_node(if_false);
+                }
+            },
+            Node::ForExprNode(node) => {
+                // the loop shares the scope it is in
+                self.resolve_node(&node.iterable);
+                self.declare_pattern(&node.pattern);
+
+                if let Node::StatementsNode(ref statements) = node.body {
+                    for statement in &statements.statements {
+                        self.resolve_node(statement);
+                    }
+                }
+            },
+            Node::FuncDefNode(node) => {
+                // default values are evaluated when the function is defined
+                for parameter in &node.parameters {
+                    if let Some(ref default) = parameter.default {
+                        self.resolve_node(default);
+                    }
+                }
+
+                self.declare(&node.identifier, parameter_arity(&node.parameters));
+                self.deferred.push(node);
+            },
+            Node::FuncCallNode(node) => {
+                self.resolve_nodes(&node.args);
+                self.resolve_kwargs(&node.kwargs);
+
+                match node.func_node {
+                    Node::VarAccessNode(ref access) => {
+                        if let Some(Binding::Function(parameters)) = self.lookup(&access.identifier) {
+                            self.check_arguments(&access.identifier, &parameters, node.args.len(), &node.kwargs);
+                        }
+                    },
+                    ref func_node => self.resolve_node(func_node),
+                }
+            },
+            Node::MethodCallNode(node) => {
+                self.resolve_node(&node.object);
+                self.resolve_nodes(&node.args);
+                self.resolve_kwargs(&node.kwargs);
+            },
+            Node::AttributeAccessNode(node) => self.resolve_node(&node.object),
+            Node::ListExprNode(node) => self.resolve_nodes(&node.elements),
+            Node::ListComprehensionNode(node) => {
+                // the pattern is only visible within the comprehension
+                self.resolve_node(&node.iterable);
+                self.scopes.push(HashMap::new());
+                self.declare_pattern(&node.pattern);
+
+                if let Some(ref condition) = node.condition {
+                    self.resolve_node(condition);
+                }
+                self.resolve_node(&node.element);
+                self.scopes.pop();
+            },
+            Node::TupleExprNode(node) => self.resolve_nodes(&node.elements),
+            Node::ReturnNode(node) => if let Some(ref value) = node.value {
+                self.resolve_node(value);
+            },
+            Node::YieldNode(node) => self.resolve_node(&node.value),
+            Node::AssertNode(node) => self.resolve_node(&node.condition),
+            Node::TryNode(node) => self.resolve_node(&node.value),
+            Node::ImportNode(node) => {
+                if !node.names.is_empty() {
+                    for name in &node.names {
+                        self.declare(name, Binding::Variable);
+                    }
+                } else if let Some(ref alias) = node.alias {
+                    self.declare(alias, Binding::Variable);
+                } else if let TokenType::String(ref path) = node.path.value {
+                    let name = module_name(path);
+                    self.current_scope().insert(name, Binding::Variable);
+                }
+            },
+            Node::StatementsNode(_) => self.resolve_body(node),
+        }
+    }
+
+    fn resolve_nodes(&mut self, nodes: &'a [Node]) {
+        for node in nodes {
+            self.resolve_node(node);
+        }
+    }
+
+    fn resolve_kwargs(&mut self, kwargs: &'a [(Token, Node)]) {
+        for (_, value) in kwargs {
+            self.resolve_node(value);
+        }
+    }
+
+    fn current_scope(&mut self) -> &mut HashMap<String, Binding> {
+        self.scopes.last_mut().expect("Resolver has no scope")
+    }
+
+    /// Bind a name in the current scope
+    fn declare(&mut self, identifier: &Token, binding: Binding) {
+        if let TokenType::Identifier(ref name) = identifier.value {
+            self.current_scope().insert(name.clone(), binding);
+        }
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern) {
+        for identifier in pattern.identifiers() {
+            self.declare(identifier, Binding::Variable);
+        }
+    }
+
+    /// Find what a name is bound to, reporting an error if it is not defined
+    fn lookup(&mut self, identifier: &Token) -> Option<Binding> {
+        let name = match identifier.value {
+            TokenType::Identifier(ref name) => name,
+            _ => return None,
+        };
+
+        let binding = self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned());
+
+        if binding.is_none() {
+            // a misspelling of a name which is in scope, or of a keyword
+            let names = self.scopes.iter().flat_map(|scope| scope.keys().map(String::as_str)).chain(Keyword::NAMES);
+            let error = Error::new_runtime(ErrorType::UndefinedVariable, format!("Undefined name '{}'", name), &identifier.position)
+                .with_suggestions(name, names);
+            self.errors.push(error);
+        }
+
+        binding
+    }
+
+    /// Ensure a function call passes an accepted number of arguments,
+    /// and that its keyword arguments name parameters which are not already given
+    fn check_arguments(&mut self, identifier: &Token, parameters: &[Argument], positional: usize, kwargs: &[(Token, Node)]) {
+        let fixed: Vec<&Argument> = parameters.iter().filter(|x| !x.variadic).collect();
+        let mut given: Vec<bool> = (0..fixed.len()).map(|i| i < positional).collect();
+
+        if positional > fixed.len() && fixed.len() == parameters.len() {
+            self.error(
+                ErrorType::ArgumentError,
+                format!("Function '{}' expects at most {}, got {}", identifier, count(fixed.len(), "argument"), positional),
+                &identifier.position
+            );
+        }
+
+        for (name, _) in kwargs {
+            let index = match fixed.iter().position(|x| x.name == name.to_string()) {
+                Some(index) => index,
+                None => {
+                    let names = fixed.iter().map(|x| x.name.as_str());
+                    let error = Error::new_runtime(ErrorType::ArgumentError, format!("Function '{}' has no parameter '{}'", identifier, name), &name.position)
+                        .with_suggestions(&name.to_string(), names);
+                    self.errors.push(error);
+                    continue;
+                },
+            };
+
+            if given[index] {
+                self.error(ErrorType::ArgumentError, format!("Argument '{}' of function '{}' is given more than once", name, identifier), &name.position);
+            }
+            given[index] = true;
+        }
+
+        let missing: Vec<String> = fixed.iter().zip(&given)
+            .filter(|(parameter, given)| parameter.required && !**given)
+            .map(|(parameter, _)| format!("'{}'", parameter.name))
+            .collect();
+
+        let noun = match missing.len() {
+            0 => return,
+            1 => "argument",
+            _ => "arguments",
+        };
+
+        self.error(
+            ErrorType::ArgumentError,
+            format!("Function '{}' is missing {} {}", identifier, noun, missing.join(", ")),
+            &identifier.position
+        );
+    }
+
+    fn error(&mut self, error_type: ErrorType, message: String, position: &TokenPosition) {
+        self.errors.push(Error::new_runtime(error_type, message, position));
+    }
+}
+
+impl<'a> Default for Resolver<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parameters of a built-in or already defined function
+fn arity(parameters: &[Parameter]) -> Binding {
+    Binding::Function(parameters.iter().map(|x| Argument {
+        name: x.name.clone(),
+        required: x.default.is_none() && !x.variadic,
+        variadic: x.variadic,
+    }).collect())
+}
+
+/// Parameters of a function definition
+fn parameter_arity(parameters: &[ParameterNode]) -> Binding {
+    Binding::Function(parameters.iter().map(|x| Argument {
+        name: match x.pattern {
+            Some(ref pattern) => pattern.to_string(),
+            None => x.identifier.to_string(),
+        },
+        required: x.default.is_none() && !x.variadic,
+        variadic: x.variadic,
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn resolve_source(text: &str) -> Result<(), Vec<Error>> {
+        let tokens = Lexer::new(text.to_string()).make_tokens().unwrap();
+        resolve(&Parser::new(tokens).parse().unwrap())
+    }
+
+    fn messages(text: &str) -> Vec<String> {
+        resolve_source(text).unwrap_err().into_iter().map(|e| e.error_message).collect()
+    }
+
+    #[test]
+    fn keyword_arguments_must_name_a_parameter() {
+        assert_eq!(messages("func f(a) { return a; }; if 0 { f(z: 1) };"), [
+            "Function 'f' is missing argument 'a'",
+            "Function 'f' has no parameter 'z'",
+        ]);
+        assert_eq!(messages("func f(a, *rest) { return a; }; f(1, rest: 2);"), ["Function 'f' has no parameter 'rest'"]);
+    }
+
+    #[test]
+    fn arguments_are_given_once() {
+        assert_eq!(messages("func f(a, b) { return a; }; f(1, a: 2);"), [
+            "Function 'f' is missing argument 'b'",
+            "Argument 'a' of function 'f' is given more than once",
+        ]);
+        assert_eq!(messages("func f(a, b) { return a; }; f();"), ["Function 'f' is missing arguments 'a', 'b'"]);
+        assert_eq!(messages("func f(a) { return a; }; f(1, 2);"), ["Function 'f' expects at most 1 argument, got 2"]);
+    }
+
+    #[test]
+    fn keyword_and_default_arguments_are_accepted() {
+        assert!(resolve_source("func f(a, b = 2, *rest) { return a; }; f(b: 1, a: 2); f(1, 2, 3, 4); int(\"1\", base: 10);").is_ok());
+    }
+}
@@ -0,0 +1,124 @@
+//! Finds names close to a misspelt one, so errors can ask "did you mean ...?"
+
+/// Most candidates suggested at once
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Number of single-character insertions, deletions, substitutions
+/// and swaps of neighbouring characters which turn one word into another
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // distances[i][j] is the distance between the first i characters of a and the first j of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = usize::from(a[i - 1] != b[j - 1]);
+
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + substitution);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+/// Candidates close enough to a name that they may be what was meant,
+/// closest first. Longer names may be further away, as they are more
+/// likely to have several typos, but a candidate which shares no
+/// characters with a short name is not suggested.
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let length = name.chars().count();
+    let furthest = (length / 3).max(1);
+
+    let mut close: Vec<(usize, &str)> = candidates.into_iter()
+        .filter(|x| *x != name)
+        .map(|x| (edit_distance(name, x), x))
+        .filter(|(distance, _)| *distance <= furthest && *distance < length)
+        .collect();
+
+    close.sort();
+    close.dedup();
+
+    close.into_iter().take(MAX_SUGGESTIONS).map(|(_, x)| x).collect()
+}
+
+/// Note asking whether one of the closest candidates was meant, if any are close
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let close: Vec<String> = closest(name, candidates).iter().map(|x| format!("`{}`", x)).collect();
+
+    match close.as_slice() {
+        [] => None,
+        [only] => Some(format!("did you mean {}?", only)),
+        [rest @ .., last] => Some(format!("did you mean {} or {}?", rest.join(", "), last)),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("print", "print"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        // insertion, deletion and substitution
+        assert_eq!(edit_distance("prnt", "print"), 1);
+        assert_eq!(edit_distance("prrint", "print"), 1);
+        assert_eq!(edit_distance("pront", "print"), 1);
+        // neighbouring characters swapped
+        assert_eq!(edit_distance("pirnt", "print"), 1);
+        assert_eq!(edit_distance("scaeld", "scaled"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn edit_distance_counts_characters_rather_than_bytes() {
+        assert_eq!(edit_distance("héllo", "hello"), 1);
+        assert_eq!(edit_distance("ü", ""), 1);
+    }
+
+    #[test]
+    fn closest_candidates_come_first() {
+        assert_eq!(closest("countr", ["count", "counter", "country", "print"]), ["count", "counter", "country"]);
+        assert_eq!(closest("lenght", ["height", "len", "length"]), ["length", "height"]);
+    }
+
+    #[test]
+    fn closest_limits_how_many_are_suggested() {
+        assert_eq!(closest("ab", ["aa", "ac", "ad", "ae"]).len(), MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn closest_skips_the_name_itself_and_distant_candidates() {
+        assert!(closest("print", ["print"]).is_empty());
+        // a single character change is always allowed, unless it replaces the whole name
+        assert!(closest("x", ["y"]).is_empty());
+        assert_eq!(closest("xs", ["ys"]), ["ys"]);
+        assert!(closest("total", ["print", "range"]).is_empty());
+        assert_eq!(closest("doubel", ["double", "trouble"]), ["double"]);
+    }
+
+    #[test]
+    fn did_you_mean_lists_the_closest_candidates() {
+        assert_eq!(did_you_mean("prnt", ["print", "int"]), Some("did you mean `print`?".to_string()));
+        assert_eq!(did_you_mean("ab", ["ac", "ad"]), Some("did you mean `ac` or `ad`?".to_string()));
+        assert_eq!(did_you_mean("ab", ["ac", "ad", "ae"]), Some("did you mean `ac`, `ad` or `ae`?".to_string()));
+        assert_eq!(did_you_mean("total", ["print"]), None);
+    }
+}